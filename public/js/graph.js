@@ -24,6 +24,17 @@ function initGraph() {
   const height = Math.min(width * 0.6, 220);
   container.style.height = height + "px";
 
+  // Replace the server-rendered graph, starting from its layout
+  const staticSvg = container.querySelector("svg.itx-graph-static");
+  if (staticSvg) staticSvg.remove();
+  const layout = window.__GRAPH_LAYOUT__;
+  if (layout) {
+    data.nodes.forEach((d) => {
+      d.x = (d.x * width) / layout.width;
+      d.y = (d.y * height) / layout.height;
+    });
+  }
+
   const isMobile = width < 500;
   const nodeRadius = isMobile ? 6 : 8;
   const linkDist = isMobile ? 60 : 100;
@@ -56,6 +67,8 @@ function initGraph() {
     .force("y", d3.forceY(height / 2).strength(0.1))
     .stop();
 
+  // Nodes already have a layout from the server, so only refine it
+  if (layout) simulation.alpha(0.3);
   for (let i = 0; i < 300; i++) simulation.tick();
 
   data.nodes.forEach((d) => {
@@ -69,7 +82,7 @@ function initGraph() {
     .data(validEdges)
     .join("line")
     .attr("stroke", C.link)
-    .attr("stroke-width", 1.5)
+    .attr("stroke-width", (d) => Math.min(1 + (d.weight || 1) * 0.5, 4))
     .attr("stroke-opacity", 0.8);
 
   const label = svg
@@ -90,7 +103,7 @@ function initGraph() {
     .selectAll("circle")
     .data(data.nodes)
     .join("circle")
-    .attr("r", nodeRadius)
+    .attr("r", (d) => nodeRadius + Math.min(d.degree || 0, 4))
     .attr("fill", C.nodeFill)
     .attr("stroke", C.nodeStroke)
     .attr("stroke-width", 1.5)
@@ -0,0 +1,26 @@
+#!/bin/sh
+# Writes .br, .gz and .zst siblings for every compressible file in the site root, so the
+# server's ServeDir can send them directly instead of the uncompressed file.
+#
+# Usage: scripts/precompress.sh [site-root]   (defaults to target/site)
+# Requires brotli, gzip and zstd on the PATH.
+set -eu
+
+SITE_ROOT="${1:-target/site}"
+
+if [ ! -d "$SITE_ROOT" ]; then
+    echo "precompress: $SITE_ROOT does not exist, run cargo leptos build first" >&2
+    exit 1
+fi
+
+find "$SITE_ROOT" -type f \( \
+    -name '*.html' -o -name '*.css' -o -name '*.js' -o -name '*.mjs' -o -name '*.wasm' \
+    -o -name '*.json' -o -name '*.svg' -o -name '*.xml' -o -name '*.txt' -o -name '*.map' \
+    -o -name '*.ttf' -o -name '*.otf' \
+\) | while read -r file; do
+    brotli --force --best --keep --output="$file.br" "$file"
+    gzip --force --best --keep "$file"
+    zstd --force --quiet -19 "$file" -o "$file.zst"
+done
+
+echo "precompress: compressed files in $SITE_ROOT"
@@ -0,0 +1,361 @@
+use crate::config::{site_config, AnalyticsConfig};
+use crate::proxy::ClientInfo;
+use crate::telemetry::METRICS_PATH;
+use axum::{
+    extract::{MatchedPath, Request},
+    http::{
+        header::{AUTHORIZATION, CACHE_CONTROL, ETAG, REFERER, USER_AGENT, WWW_AUTHENTICATE},
+        HeaderMap, HeaderValue, Method, StatusCode,
+    },
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use chrono::{DateTime, Utc};
+use ring::hmac;
+use serde::{Deserialize, Serialize};
+use std::{collections::HashSet, net::IpAddr, path::Path, sync::OnceLock};
+use tokio::{io::AsyncWriteExt, sync::mpsc};
+
+pub const FEED_PATH: &str = "/feed.xml";
+pub const ADMIN_PREFIX: &str = "/admin";
+const ADMIN_USER: &str = "admin";
+
+// Lowercase user agent fragments of crawlers, link previews, monitors and scripts.
+const BOT_AGENTS: [&str; 20] = [
+    "bot", "crawl", "spider", "slurp", "archiver", "curl", "wget", "python", "go-http-client",
+    "java/", "okhttp", "headless", "lighthouse", "pingdom", "uptime", "monitor", "preview",
+    "facebookexternalhit", "embedly", "scrapy",
+];
+
+// Views waiting to be written. Past this, new views are dropped rather than held in memory.
+const EVENT_QUEUE_SIZE: usize = 1024;
+
+static ANALYTICS: OnceLock<Analytics> = OnceLock::new();
+
+// What a view is recorded as, one per line in the analytics file.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ViewEvent {
+    pub time: DateTime<Utc>,
+    pub kind: ViewKind,
+    pub path: String,
+    // Host of the referring site, for visits from outside.
+    pub referrer: Option<String>,
+    // ISO code, when a GeoIP database is available.
+    pub country: Option<String>,
+    // Keyed hash of the address and user agent with a key that changes every day, so visitors
+    // can be counted for a day but not recognised across days.
+    pub visitor: String,
+    // Readers an aggregator reports in its user agent, e.g. `Feedly/1.0 (...; 42 subscribers)`.
+    pub subscribers: Option<u64>,
+    // Name of the aggregator fetching the feed, for de-duplicating its reports.
+    pub agent: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ViewKind {
+    Page,
+    Feed,
+}
+
+struct Analytics {
+    sender: mpsc::Sender<ViewEvent>,
+    geoip: Option<maxminddb::Reader<Vec<u8>>>,
+    // Paths of the leptos routes, e.g. `/blog/:post`. Only these count as page views.
+    page_routes: HashSet<String>,
+    secret: Vec<u8>,
+}
+
+// Starts the writer for the analytics file. Views are not recorded unless this was called.
+pub fn init_analytics(config: &AnalyticsConfig, page_routes: impl IntoIterator<Item = String>) {
+    if !config.enabled {
+        return;
+    }
+    let geoip = if Path::new(&config.geoip_db).exists() {
+        match maxminddb::Reader::open_readfile(&config.geoip_db) {
+            Ok(reader) => Some(reader),
+            Err(e) => {
+                tracing::warn!("unable to open geoip database {}: {}", config.geoip_db, e);
+                None
+            }
+        }
+    } else {
+        None
+    };
+
+    let (sender, receiver) = mpsc::channel(EVENT_QUEUE_SIZE);
+    tokio::spawn(write_events(config.path.clone(), receiver));
+    let _ = ANALYTICS.set(Analytics {
+        sender,
+        geoip,
+        page_routes: page_routes
+            .into_iter()
+            .filter(|route| !route.starts_with(ADMIN_PREFIX))
+            .collect(),
+        secret: load_secret(&config.secret_path),
+    });
+}
+
+// Reads the secret visitor ids are keyed with, creating it on first start. Without a readable one, a secret for
+// this process only is used, so visitors are counted again after a restart.
+fn load_secret(path: &str) -> Vec<u8> {
+    match std::fs::read(path) {
+        Ok(secret) if !secret.is_empty() => return secret,
+        Ok(_) => {}
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+        Err(e) => {
+            tracing::warn!("unable to read {}: {}", path, e);
+            return rand::random::<[u8; 32]>().to_vec();
+        }
+    }
+    let secret = format!("{:032x}", rand::random::<u128>()).into_bytes();
+    let written = Path::new(path)
+        .parent()
+        .filter(|dir| !dir.as_os_str().is_empty())
+        .map_or(Ok(()), std::fs::create_dir_all)
+        .and_then(|_| write_private(path, &secret));
+    if let Err(e) = written {
+        tracing::warn!("unable to create {}: {}", path, e);
+    }
+    secret
+}
+
+fn write_private(path: &str, contents: &[u8]) -> std::io::Result<()> {
+    use std::io::Write;
+
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+    options.open(path)?.write_all(contents)
+}
+
+async fn write_events(path: String, mut receiver: mpsc::Receiver<ViewEvent>) {
+    if let Some(dir) = Path::new(&path).parent().filter(|dir| !dir.as_os_str().is_empty()) {
+        if let Err(e) = tokio::fs::create_dir_all(dir).await {
+            tracing::warn!("unable to create {}: {}", dir.display(), e);
+        }
+    }
+    let mut file = match tokio::fs::OpenOptions::new().create(true).append(true).open(&path).await {
+        Ok(file) => file,
+        Err(e) => {
+            tracing::warn!("analytics disabled, unable to open {}: {}", path, e);
+            return;
+        }
+    };
+    while let Some(event) = receiver.recv().await {
+        let Ok(mut line) = serde_json::to_string(&event) else { continue };
+        line.push('\n');
+        if let Err(e) = file.write_all(line.as_bytes()).await {
+            tracing::warn!("unable to write to {}: {}", path, e);
+        }
+    }
+}
+
+// Records successful GETs of pages and of the feed, leaving out bots and prefetches.
+pub async fn record_views(req: Request, next: Next) -> Response {
+    let Some(analytics) = ANALYTICS.get() else {
+        return next.run(req).await;
+    };
+    let route = req.extensions().get::<MatchedPath>().map(|matched| matched.as_str().to_string());
+    let kind = match route.as_deref() {
+        Some(FEED_PATH) => ViewKind::Feed,
+        Some(route) if analytics.page_routes.contains(route) => ViewKind::Page,
+        _ => return next.run(req).await,
+    };
+    if req.method() != Method::GET || is_prefetch(req.headers()) {
+        return next.run(req).await;
+    }
+
+    let user_agent = header(req.headers(), USER_AGENT.as_str()).unwrap_or_default();
+    let bot = is_bot(&user_agent);
+    // Aggregators are bots too, but their fetches are what the subscriber estimate is made of.
+    if bot && kind == ViewKind::Page {
+        return next.run(req).await;
+    }
+    let path = req.uri().path().to_string();
+    let client = req.extensions().get::<ClientInfo>().cloned();
+    let referrer = header(req.headers(), REFERER.as_str()).and_then(|referrer| {
+        external_host(&referrer, client.as_ref().and_then(|client| client.host.as_deref()))
+    });
+
+    let response = next.run(req).await;
+    if !matches!(response.status(), StatusCode::OK | StatusCode::NOT_MODIFIED) {
+        return response;
+    }
+
+    let time = Utc::now();
+    let ip = client.and_then(|client| client.ip);
+    let (subscribers, agent) = match kind {
+        ViewKind::Feed => feed_subscribers(&user_agent),
+        ViewKind::Page => (None, None),
+    };
+    let sent = analytics.sender.try_send(ViewEvent {
+        time,
+        kind,
+        path,
+        referrer,
+        country: ip.and_then(|ip| analytics.country(ip)),
+        visitor: analytics.visitor(&time, ip, &user_agent),
+        subscribers,
+        agent,
+    });
+    if let Err(mpsc::error::TrySendError::Full(_)) = sent {
+        tracing::warn!("analytics queue full, dropping a view");
+    }
+    response
+}
+
+impl Analytics {
+    fn country(&self, ip: IpAddr) -> Option<String> {
+        let country: maxminddb::geoip2::Country = self.geoip.as_ref()?.lookup(ip).ok()?;
+        country.country?.iso_code.map(str::to_string)
+    }
+
+    // HMAC-SHA256 of the address and user agent, keyed by a daily key derived from the secret,
+    // so the id can't be reversed or linked across days without the secret.
+    fn visitor(&self, time: &DateTime<Utc>, ip: Option<IpAddr>, user_agent: &str) -> String {
+        let secret = hmac::Key::new(hmac::HMAC_SHA256, &self.secret);
+        let day = hmac::sign(&secret, time.format("%Y-%m-%d").to_string().as_bytes());
+        let key = hmac::Key::new(hmac::HMAC_SHA256, day.as_ref());
+        let client = format!("{}|{}", ip.map(|ip| ip.to_string()).unwrap_or_default(), user_agent);
+        // 64 bits tell a day's visitors apart well enough.
+        let tag = hmac::sign(&key, client.as_bytes());
+        let mut id = [0; 8];
+        id.copy_from_slice(&tag.as_ref()[..8]);
+        format!("{:016x}", u64::from_be_bytes(id))
+    }
+}
+
+fn header(headers: &HeaderMap, name: &str) -> Option<String> {
+    headers.get(name).and_then(|value| value.to_str().ok()).map(str::to_string)
+}
+
+fn is_prefetch(headers: &HeaderMap) -> bool {
+    ["purpose", "sec-purpose", "x-moz"]
+        .iter()
+        .filter_map(|name| header(headers, name))
+        .any(|value| value.contains("prefetch"))
+}
+
+fn is_bot(user_agent: &str) -> bool {
+    let user_agent = user_agent.to_lowercase();
+    user_agent.is_empty() || BOT_AGENTS.iter().any(|bot| user_agent.contains(bot))
+}
+
+// Host of `referrer`, unless it is this site.
+fn external_host(referrer: &str, own_host: Option<&str>) -> Option<String> {
+    let (_, rest) = referrer.split_once("://")?;
+    let host = rest.split(['/', '?', '#']).next()?.to_lowercase();
+    let host = host.rsplit('@').next().unwrap_or(&host).to_string();
+    let own_domain = site_config().domain().to_lowercase();
+    let is_own = |own: &str| host == own || host.strip_prefix("www.") == Some(own);
+    if host.is_empty() || is_own(&own_domain) || own_host.is_some_and(|own| is_own(&own.to_lowercase())) {
+        return None;
+    }
+    Some(host)
+}
+
+// Parses the `N subscribers` (or `N readers`) count aggregators put in their user agent.
+fn feed_subscribers(user_agent: &str) -> (Option<u64>, Option<String>) {
+    let words: Vec<&str> = user_agent
+        .split(|c: char| c.is_whitespace() || c == ';' || c == '(' || c == ')')
+        .filter(|word| !word.is_empty())
+        .collect();
+    let subscribers = words.windows(2).find_map(|pair| {
+        let label = pair[1].to_lowercase();
+        (label.starts_with("subscriber") || label.starts_with("reader"))
+            .then(|| pair[0].parse().ok())
+            .flatten()
+    });
+    let agent = subscribers.and(words.first().map(|name| name.split('/').next().unwrap_or(name).to_string()));
+    (subscribers, agent)
+}
+
+// Checks the basic auth credentials of a request against `admin_password`.
+pub fn is_admin(headers: &HeaderMap) -> bool {
+    use base64::Engine;
+
+    let Some(password) = &site_config().analytics.admin_password else {
+        return false;
+    };
+    let Some(credentials) = header(headers, AUTHORIZATION.as_str())
+        .and_then(|value| value.strip_prefix("Basic ").map(str::to_string))
+        .and_then(|encoded| base64::engine::general_purpose::STANDARD.decode(encoded.trim()).ok())
+    else {
+        return false;
+    };
+    let expected = format!("{}:{}", ADMIN_USER, password);
+    constant_time_eq(&credentials, expected.as_bytes())
+}
+
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+// Guards everything under `/admin`, and `/metrics` since it lists the views of every post:
+// 404 while no password is configured, otherwise a basic auth challenge. Scrapers log in as
+// `admin` too.
+pub async fn require_admin(req: Request, next: Next) -> Response {
+    let path = req.uri().path();
+    if !path.starts_with(ADMIN_PREFIX) && path != METRICS_PATH {
+        return next.run(req).await;
+    }
+    if site_config().analytics.admin_password.is_none() {
+        return StatusCode::NOT_FOUND.into_response();
+    }
+    if !is_admin(req.headers()) {
+        return (
+            StatusCode::UNAUTHORIZED,
+            [(WWW_AUTHENTICATE, r#"Basic realm="admin", charset="UTF-8""#)],
+        )
+            .into_response();
+    }
+    let mut response = next.run(req).await;
+    // Never stored by shared caches, whatever the cache layer decided.
+    let headers = response.headers_mut();
+    headers.insert(CACHE_CONTROL, HeaderValue::from_static("private, no-store"));
+    headers.remove(ETAG);
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn analytics(secret: &[u8]) -> Analytics {
+        Analytics {
+            sender: mpsc::channel(1).0,
+            geoip: None,
+            page_routes: HashSet::new(),
+            secret: secret.to_vec(),
+        }
+    }
+
+    #[test]
+    fn visitor_hash_changes_with_the_day_and_the_secret() {
+        let ip = Some(IpAddr::from([203, 0, 113, 9]));
+        let morning: DateTime<Utc> = "2026-10-19T08:00:00Z".parse().unwrap();
+        let evening: DateTime<Utc> = "2026-10-19T22:00:00Z".parse().unwrap();
+        let next_day: DateTime<Utc> = "2026-10-20T08:00:00Z".parse().unwrap();
+
+        let site = analytics(b"secret");
+        let visitor = site.visitor(&morning, ip, "Firefox");
+        assert_eq!(visitor, site.visitor(&evening, ip, "Firefox"));
+        assert_ne!(visitor, site.visitor(&next_day, ip, "Firefox"));
+        assert_ne!(visitor, site.visitor(&morning, ip, "Chrome"));
+        assert_ne!(visitor, analytics(b"other").visitor(&morning, ip, "Firefox"));
+        assert_eq!(visitor.len(), 16);
+    }
+
+    #[test]
+    fn secret_is_created_once_and_kept() {
+        let dir = std::env::temp_dir().join(format!("itx-analytics-{}", std::process::id()));
+        let path = dir.join("analytics.secret");
+        let path = path.to_str().unwrap();
+        let secret = load_secret(path);
+        assert_eq!(secret.len(), 32);
+        assert_eq!(load_secret(path), secret);
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
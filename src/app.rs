@@ -1,22 +1,40 @@
 use crate::{
-    error_template::{AppError, ErrorTemplate},
-    routes::{about::About, blog::{blog_article::RenderBlogPost, blog_section::BlogPost}, graph::GraphPage, home::Home, writing::{writing_article::RenderWritingPost, writing_section::WritingPost}},
+    config::{provide_site_config, site_config_json, SITE_CONFIG_ELEMENT_ID},
+    error_template::{error_page, outside_error, AppError, OUTSIDE_ERROR_ELEMENT_ID},
+    config::CollectionConfig,
+    routes::{about::About, admin::AdminStats, collection::{CollectionArticle, CollectionSection}, graph::GraphPage, home::Home},
     server_functions::posts::{get_last_update, get_posts},
 };
-use leptos::*;
+use leptos::{nonce::use_nonce, *};
 use leptos_meta::*;
 use leptos_router::*;
 
 #[component]
 pub fn App() -> impl IntoView {
     provide_meta_context();
-    let posts = create_blocking_resource(|| (), |_| async move { get_posts().await });
+    let site_config = provide_site_config();
+    let collections = site_config.collections.clone();
+    // The posts aren't loaded for pages that only show an error.
+    let outside_error = outside_error();
+    let load_posts = outside_error.is_none();
+    let posts = create_blocking_resource(
+        || (),
+        move |_| async move {
+            if load_posts {
+                get_posts().await
+            } else {
+                Ok(Default::default())
+            }
+        },
+    );
     provide_context(posts);
 
     let last_update = create_resource(|| (), |_| async move { get_last_update().await });
     provide_context(last_update);
+    #[cfg(feature = "ssr")]
+    crate::security::expose_nonce();
     view! {
-        <Stylesheet id="leptos" href="/pkg/itehax-website.css" />
+        <Stylesheet id="leptos" href=stylesheet_href() />
         <Link rel="shortcut icon" type_="image/png" href="/phrack_icon1.png" />
         <Link
             href="https://fonts.googleapis.com/css2?family=Anonymous+Pro:ital,wght@0,400;0,700;1,400;1,700&display=swap"
@@ -25,46 +43,70 @@ pub fn App() -> impl IntoView {
         // Early theme init: read localStorage before first paint to avoid flash
         <Script>{r#"(function(){var t=localStorage.getItem('theme')||'dark';document.documentElement.setAttribute('data-theme',t);})();"#}</Script>
         // <Link href="/fonts/ibm.css" rel="stylesheet"/>
-        <Router fallback=|| {
-            let mut outside_errors = Errors::default();
-            outside_errors.insert_with_default_key(AppError::NotFound);
-            view! { <ErrorTemplate outside_errors /> }.into_view()
-        }>
-            <Routes>
-                <Route path="" view=Home />
-
-                <Route path="/blog" view=BlogPost />
+        <script
+            type="application/json"
+            id=SITE_CONFIG_ELEMENT_ID
+            inner_html=site_config_json(&site_config)
+        ></script>
+        <Router fallback=|| error_page(AppError::NotFound)>
+            {match outside_error {
+                Some(error) => {
+                    let json = serde_json::to_string(&error).unwrap_or_default();
+                    view! {
+                        <script type="application/json" id=OUTSIDE_ERROR_ELEMENT_ID inner_html=json></script>
+                        {error_page(error)}
+                    }
+                        .into_view()
+                }
+                None => view! { <Routes children=Box::new(move || app_routes(&collections)) /> }.into_view(),
+            }}
+            <script nonce=use_nonce() src="/preline/preline.js"></script>
+        </Router>
+    }
+}
 
+// `<Routes/>` only picks up `<Route/>`s that are its direct children, so the routes of every
+// collection are added to the same fragment as the fixed pages.
+fn app_routes(collections: &[CollectionConfig]) -> Fragment {
+    let mut routes = vec![
+        view! { <Route path="" view=Home /> }.into_view(),
+        view! { <Route path="/about" view=About /> }.into_view(),
+        view! { <Route path="/graph" view=GraphPage /> }.into_view(),
+        view! { <Route path="/admin/stats" view=AdminStats /> }.into_view(),
+        // <Route path="/manifesto" view=Manifesto /> TBA
+    ];
+    for collection in collections.iter().filter(|collection| collection.routes) {
+        let section = collection.clone();
+        let article = collection.clone();
+        routes.push(
+            view! {
                 <Route
-                    path="/blog/:post"
-                    view=move || {
-                        view! {
-                            <Link rel="stylesheet" href="/highlighter/styles/github.min.css" />
-                            <Link
-                                rel="stylesheet"
-                                href=r#"https://cdn.jsdelivr.net/npm/katex@0.16.11/dist/katex.min.css"#
-                                integrity="sha384-nB0miv6/jRmo5UMMR1wu3Gz6NLsoTkbqJghGIsx//Rlm+ZU03BU6SQNC66uf4l5+"
-                                crossorigin="anonymous"
-                            />
-                            <Link rel="stylesheet" href="/highlighter/styles/katex.css" />
-
-                            <script defer src="/highlighter/load_highlight.js"></script>
-                            <RenderBlogPost />
-                        }
-                    }
+                    path=collection.path()
+                    view=move || view! { <CollectionSection collection=section.clone() /> }
                 />
+            }
+                .into_view(),
+        );
+        routes.push(
+            view! {
+                <Route
+                    path=format!("{}/:post", collection.path())
+                    view=move || view! { <CollectionArticle collection=article.clone() /> }
+                />
+            }
+                .into_view(),
+        );
+    }
+    Fragment::new(routes)
+}
 
-                <Route path="/about" view=About />
-                <Route path="/graph" view=GraphPage />
-                // <Route path="/manifesto" view=Manifesto /> TBA
-
-                <Route path="/writing" view=WritingPost />
-                <Route path="/writing/:post" view=RenderWritingPost />
-
-            // <Route path="/projects" view=ProjectsPost />
-            // <Route path="/projects/:post" view=RenderProjectsPost />
-            </Routes>
-            <script src="/preline/preline.js"></script>
-        </Router>
+// The browser keeps the link rendered by the server, so only the server needs the fingerprinted name.
+fn stylesheet_href() -> String {
+    cfg_if::cfg_if! {
+        if #[cfg(feature = "ssr")] {
+            crate::cache::pkg_files().css_href()
+        } else {
+            "/pkg/itehax-website.css".to_string()
+        }
     }
 }
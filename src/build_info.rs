@@ -0,0 +1,35 @@
+use chrono::Utc;
+use serde::Serialize;
+use std::sync::OnceLock;
+
+const UNKNOWN: &str = "unknown";
+
+static BUILD_INFO: OnceLock<BuildInfo> = OnceLock::new();
+
+// What is running, as passed to the docker build by the deploy workflow
+// (`GIT_COMMIT`, `BUILD_TIME` and `LAST_UPDATED`).
+#[derive(Debug, Clone, Serialize)]
+pub struct BuildInfo {
+    pub commit: String,
+    pub build_time: String,
+    // Date of the last commit, shown in the footer.
+    pub last_updated: String,
+}
+
+impl BuildInfo {
+    // Outside of a docker build, e.g. with `cargo leptos watch`, the server start time stands
+    // in for the build time and the last update.
+    fn from_env() -> Self {
+        let var = |key: &str| std::env::var(key).ok().filter(|value| !value.is_empty());
+        let build_time = var("BUILD_TIME").unwrap_or_else(|| Utc::now().format("%Y/%m/%d %H:%M").to_string());
+        Self {
+            commit: var("GIT_COMMIT").unwrap_or_else(|| UNKNOWN.to_string()),
+            last_updated: var("LAST_UPDATED").unwrap_or_else(|| build_time.clone()),
+            build_time,
+        }
+    }
+}
+
+pub fn build_info() -> &'static BuildInfo {
+    BUILD_INFO.get_or_init(BuildInfo::from_env)
+}
@@ -0,0 +1,260 @@
+use crate::analytics::ADMIN_PREFIX;
+use crate::build_info::build_info;
+use crate::config::site_config;
+use crate::content::{content, current_hash};
+use crate::state::AppState;
+use axum::{
+    extract::{Request, State},
+    http::{
+        header::{CACHE_CONTROL, CONTENT_TYPE, ETAG, IF_NONE_MATCH, LAST_MODIFIED},
+        HeaderMap, HeaderValue, Method, StatusCode,
+    },
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use leptos::LeptosOptions;
+use std::sync::OnceLock;
+
+const IMMUTABLE: &str = "public, max-age=31536000, immutable";
+// Unhashed files keep their name across deploys, so they are always revalidated.
+const REVALIDATE: &str = "no-cache";
+
+// Directories under the site root whose files are named by their content, see `crate::images`.
+// Originals and fonts keep their name when replaced, so they are revalidated like the rest.
+const IMMUTABLE_DIRS: [&str; 1] = ["/images/responsive/"];
+
+static PKG_FILES: OnceLock<PkgFiles> = OnceLock::new();
+static BUILD_HASH: OnceLock<u64> = OnceLock::new();
+
+// Names of the js, wasm and css bundles, including the hashes cargo-leptos writes to
+// `hash.txt` when `hash-files` is enabled.
+#[derive(Debug, Default)]
+pub struct PkgFiles {
+    pub output_name: String,
+    pub js_hash: String,
+    pub wasm_hash: String,
+    pub css_hash: String,
+}
+
+impl PkgFiles {
+    fn load(options: &LeptosOptions) -> Self {
+        let mut files = PkgFiles {
+            output_name: options.output_name.clone(),
+            ..Default::default()
+        };
+        if !options.hash_files {
+            return files;
+        }
+
+        let hash_path = std::env::current_exe()
+            .ok()
+            .and_then(|path| path.parent().map(|dir| dir.join(&options.hash_file)))
+            .unwrap_or_default();
+        match std::fs::read_to_string(&hash_path) {
+            Ok(hashes) => {
+                for (ext, hash) in hashes.lines().filter_map(|line| line.split_once(':')) {
+                    let hash = hash.trim().to_string();
+                    match ext.trim() {
+                        "js" => files.js_hash = hash,
+                        "wasm" => files.wasm_hash = hash,
+                        "css" => files.css_hash = hash,
+                        _ => {}
+                    }
+                }
+            }
+            Err(e) => leptos::logging::warn!("unable to read {}: {}", hash_path.display(), e),
+        }
+        files
+    }
+
+    // e.g. `/pkg/itehax-website.1a2b3c.css`, or `/pkg/itehax-website.css` without hashes.
+    pub fn css_href(&self) -> String {
+        if self.css_hash.is_empty() {
+            format!("/pkg/{}.css", self.output_name)
+        } else {
+            format!("/pkg/{}.{}.css", self.output_name, self.css_hash)
+        }
+    }
+
+    fn is_fingerprinted(&self, path: &str) -> bool {
+        let Some(file) = path.strip_prefix("/pkg/") else { return false };
+        [&self.js_hash, &self.wasm_hash, &self.css_hash]
+            .iter()
+            .any(|hash| !hash.is_empty() && file.contains(hash.as_str()))
+    }
+}
+
+// Called once from `main`, before the first page is rendered.
+pub fn init_pkg_files(options: &LeptosOptions) -> &'static PkgFiles {
+    PKG_FILES.get_or_init(|| PkgFiles::load(options))
+}
+
+pub fn pkg_files() -> &'static PkgFiles {
+    PKG_FILES.get_or_init(PkgFiles::default)
+}
+
+// Adds Cache-Control to every response and an ETag to rendered pages and feeds, answering
+// `If-None-Match` with `304 Not Modified`. The ETag names the url, the posts and the build the
+// page was rendered from rather than its bytes, so the body streams through untouched and a
+// revalidation is answered without rendering the page again.
+pub async fn cache_headers(State(state): State<AppState>, req: Request, next: Next) -> Response {
+    let path = req.uri().path().to_string();
+    let uri = req.uri().to_string();
+    let cacheable = matches!(*req.method(), Method::GET | Method::HEAD);
+    let if_none_match = req.headers().get(IF_NONE_MATCH).cloned();
+    if !cacheable || path.starts_with("/api/") {
+        return next.run(req).await;
+    }
+    // Pages stream, so they may read the posts only after the headers are sent. Reloading
+    // them first means the page renders from posts at least as new as the ETag names.
+    let rendered_from = tokio::task::spawn_blocking(|| content().hash).await.ok();
+    // Admin pages change with the analytics, which the content hash doesn't name.
+    let rendered_from = rendered_from.filter(|_| !path.starts_with(ADMIN_PREFIX));
+
+    // Only urls that were answered with one of these tags can be revalidated with it, so a
+    // match means the page would render the same. `*` matches any page, including one that
+    // no longer exists, so it waits for the render below.
+    if let (Some(hash), Some(value)) = (rendered_from, &if_none_match) {
+        for html in [true, false] {
+            let etag = etag(hash, &uri, html);
+            if !is_wildcard(value) && etag_matches(value, &etag) {
+                return not_modified(&etag, &page_cache_control(&state, html));
+            }
+        }
+    }
+
+    let mut response = next.run(req).await;
+    // Handlers that set their own Cache-Control, like `/metrics`, are left alone.
+    if response.status() != StatusCode::OK || response.headers().contains_key(CACHE_CONTROL) {
+        return response;
+    }
+
+    // Static files come from `ServeDir`, which already validates with Last-Modified.
+    if response.headers().contains_key(LAST_MODIFIED) {
+        let cache_control = if pkg_files().is_fingerprinted(&path)
+            || IMMUTABLE_DIRS.iter().any(|dir| path.starts_with(dir))
+        {
+            IMMUTABLE
+        } else {
+            REVALIDATE
+        };
+        return with_cache_control(response, cache_control);
+    }
+
+    let html = is_html(response.headers());
+    let cache_control = page_cache_control(&state, html);
+    // The posts may have been reloaded again while the page was rendering.
+    let Some(hash) = rendered_from.filter(|&hash| hash == current_hash()) else {
+        return with_cache_control(response, &cache_control);
+    };
+    let etag = etag(hash, &uri, html);
+    if if_none_match.is_some_and(|value| etag_matches(&value, &etag)) {
+        return not_modified(&etag, &cache_control);
+    }
+    if let Ok(value) = HeaderValue::from_str(&etag) {
+        response.headers_mut().insert(ETAG, value);
+    }
+    with_cache_control(response, &cache_control)
+}
+
+// Weak, since the compression layer may still change the encoding of the body. Whether the
+// page is html is part of the tag, so a 304 gets the Cache-Control of the page it stands for.
+fn etag(content: u64, uri: &str, html: bool) -> String {
+    format!("W/\"{:016x}-{}\"", page_hash(content, uri), if html { "h" } else { "f" })
+}
+
+fn page_cache_control(state: &AppState, html: bool) -> String {
+    let max_age = if html {
+        state.site_config.cache.html_max_age
+    } else {
+        state.site_config.cache.feed_max_age
+    };
+    format!("public, max-age={}, must-revalidate", max_age)
+}
+
+// A fresh response rather than a rendered one: it must not carry a page's CSP nonce, see
+// `security_headers`.
+fn not_modified(etag: &str, cache_control: &str) -> Response {
+    let mut not_modified = StatusCode::NOT_MODIFIED.into_response();
+    if let Ok(value) = HeaderValue::from_str(etag) {
+        not_modified.headers_mut().insert(ETAG, value);
+    }
+    with_cache_control(not_modified, cache_control)
+}
+
+// A page only changes with its url, the posts it shows, the code that renders it and the site
+// config.
+fn page_hash(content: u64, uri: &str) -> u64 {
+    let build = *BUILD_HASH.get_or_init(|| {
+        let info = build_info();
+        let config = serde_json::to_string(site_config()).unwrap_or_default();
+        xxhash_rust::xxh3::xxh3_64(format!("{}\n{}\n{}", info.commit, info.build_time, config).as_bytes())
+    });
+    xxhash_rust::xxh3::xxh3_64_with_seed(format!("{}\n{}", content, uri).as_bytes(), build)
+}
+
+fn with_cache_control(mut response: Response, cache_control: &str) -> Response {
+    if let Ok(value) = HeaderValue::from_str(cache_control) {
+        response.headers_mut().insert(CACHE_CONTROL, value);
+    }
+    response
+}
+
+fn is_html(headers: &HeaderMap) -> bool {
+    headers
+        .get(CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value.starts_with("text/html"))
+}
+
+fn is_wildcard(if_none_match: &HeaderValue) -> bool {
+    if_none_match.to_str().is_ok_and(|value| value.split(',').any(|tag| tag.trim() == "*"))
+}
+
+// `If-None-Match` holds a comma separated list of tags, possibly weak (`W/"..."`), or `*`.
+fn etag_matches(if_none_match: &HeaderValue, etag: &str) -> bool {
+    let Ok(value) = if_none_match.to_str() else { return false };
+    value
+        .split(',')
+        .map(|tag| tag.trim())
+        .any(|tag| tag == "*" || tag.trim_start_matches("W/") == etag.trim_start_matches("W/"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ETAG_VALUE: &str = r#"W/"00000000000000ff""#;
+
+    fn matches(if_none_match: &str) -> bool {
+        etag_matches(&HeaderValue::from_str(if_none_match).unwrap(), ETAG_VALUE)
+    }
+
+    #[test]
+    fn etag_matches_weak_and_strong_forms() {
+        assert!(matches(r#"W/"00000000000000ff""#));
+        assert!(matches(r#""00000000000000ff""#));
+        assert!(!matches(r#"W/"00000000000000fe""#));
+    }
+
+    #[test]
+    fn etag_matches_any_tag_in_a_list() {
+        assert!(matches(r#""a", W/"00000000000000ff" , "b""#));
+        assert!(!matches(r#""a","b""#));
+        assert!(matches("*"));
+    }
+
+    #[test]
+    fn page_hash_follows_the_content_and_url() {
+        assert_eq!(page_hash(1, "/blog"), page_hash(1, "/blog"));
+        assert_ne!(page_hash(1, "/blog"), page_hash(2, "/blog"));
+        assert_ne!(page_hash(1, "/blog"), page_hash(1, "/blog?tag=rust"));
+    }
+
+    #[test]
+    fn etag_names_the_kind_of_page() {
+        assert_ne!(etag(1, "/rss.xml", true), etag(1, "/rss.xml", false));
+        assert!(is_wildcard(&HeaderValue::from_static(r#""a", *"#)));
+        assert!(!is_wildcard(&HeaderValue::from_static(ETAG_VALUE)));
+    }
+}
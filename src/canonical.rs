@@ -0,0 +1,55 @@
+use crate::config::{is_dev_mode, site_config};
+use crate::proxy::ClientInfo;
+use axum::{
+    extract::Request,
+    http::{header::LOCATION, Method, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use std::net::IpAddr;
+
+// Sends every page to a single url: the host of `base_url`, without a trailing slash. Search
+// engines and feed readers would otherwise see `www.` and `/blog/` as separate pages.
+pub async fn canonical_redirect(req: Request, next: Next) -> Response {
+    let config = site_config();
+    if !config.server.canonical_redirects || is_dev_mode() {
+        return next.run(req).await;
+    }
+    // Server functions are posted to their exact path and never linked to.
+    let path = req.uri().path();
+    if !matches!(*req.method(), Method::GET | Method::HEAD) || path.starts_with("/api/") {
+        return next.run(req).await;
+    }
+
+    let host = req
+        .extensions()
+        .get::<ClientInfo>()
+        .and_then(|client| client.host.clone())
+        .unwrap_or_default()
+        .to_lowercase();
+    // Health checks and local requests reach the server by address, not by name.
+    let wrong_host = !host.is_empty() && host != config.domain().to_lowercase() && !is_local(&host);
+    let canonical_path = match path.trim_end_matches('/') {
+        "" => "/",
+        trimmed => trimmed,
+    };
+    if !wrong_host && canonical_path == path {
+        return next.run(req).await;
+    }
+
+    let query = req.uri().query().map(|query| format!("?{}", query)).unwrap_or_default();
+    let target = format!("{}{}", canonical_path, query);
+    // Local requests stay local, only the path is fixed.
+    let location = if wrong_host { config.absolute_url(&target) } else { target };
+    (StatusCode::MOVED_PERMANENTLY, [(LOCATION, location)]).into_response()
+}
+
+// Whether `host` (with an optional port) is an address or localhost.
+fn is_local(host: &str) -> bool {
+    let name = match host.rsplit_once(':') {
+        Some((name, port)) if port.chars().all(|c| c.is_ascii_digit()) && !name.ends_with(':') => name,
+        _ => host,
+    };
+    let name = name.trim_start_matches('[').trim_end_matches(']');
+    name == "localhost" || name.parse::<IpAddr>().is_ok()
+}
@@ -0,0 +1,63 @@
+use crate::components::error::InlineError;
+use crate::error_template::AppError;
+use crate::server_functions::backlinks::get_backlinks;
+use leptos::*;
+
+#[component]
+pub fn Backlinks(#[prop(into)] post_href: String) -> impl IntoView {
+    let backlinks = create_resource(
+        move || post_href.clone(),
+        |post_href| async move { get_backlinks(post_href).await },
+    );
+
+    view! {
+        <Suspense fallback=move || {
+            view! { <p class="text-[#8B949E]">"Loading backlinks..."</p> }
+        }>
+            {move || {
+                backlinks
+                    .get()
+                    .map(|result| match result {
+                        Ok(backlinks) if !backlinks.is_empty() => {
+                            view! {
+                                <div class="mt-16 border border-[#30363D] rounded-xl p-4 bg-[#161B22]">
+                                    <h3 class="text-sm font-medium text-[#8B949E] mb-4 text-center">
+                                        "Linked from"
+                                    </h3>
+                                    <ul class="space-y-4">
+                                        {backlinks
+                                            .into_iter()
+                                            .map(|backlink| {
+                                                view! {
+                                                    <li>
+                                                        <a
+                                                            class="font-semibold text-blue-500 hover:text-blue-400"
+                                                            href=backlink.source_href
+                                                        >
+                                                            {backlink.source_title}
+                                                        </a>
+                                                        <p class="mt-1 text-sm text-[#8B949E]">
+                                                            {backlink.context}
+                                                        </p>
+                                                    </li>
+                                                }
+                                            })
+                                            .collect_view()}
+                                    </ul>
+                                </div>
+                            }
+                                .into_view()
+                        }
+                        Ok(_) => View::default(),
+                        Err(e) => {
+                            view! {
+                                <InlineError error=AppError::from(e) what="backlinks" />
+                            }
+                                .into_view()
+                        }
+                    })
+            }}
+
+        </Suspense>
+    }
+}
@@ -0,0 +1,416 @@
+use crate::escape::escape_xml;
+use crate::server_functions::cast::get_cast;
+use leptos::*;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+// Rendered posts mark every recording with these comments, around a static transcript that
+// readers without javascript and feed readers get instead of the player.
+const CAST_START: &str = "<!--itx-cast ";
+const CAST_END: &str = "<!--/itx-cast-->";
+const BODY_START: &str = r#"<div class="itx-term-body">"#;
+const BODY_END: &str = "</div></div>";
+
+// How often the player advances while playing.
+const FRAME: Duration = Duration::from_millis(50);
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct CastMeta {
+    pub src: String,
+    pub title: String,
+}
+
+// A rendered post split around its recordings.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ContentSegment {
+    Html(String),
+    // The transcript is the html shown until the recording is played.
+    Cast { meta: CastMeta, transcript: String },
+}
+
+// Static html for a recording, using the same panel as `[!term]` blocks.
+pub fn cast_html(meta: &CastMeta, transcript: &str) -> String {
+    // `--` would end the comment early, and can only appear inside json strings.
+    let json = serde_json::to_string(meta).unwrap_or_default().replace("--", "-\\u002d");
+    format!(
+        r#"{start}{json}--><div class="itx-term-block itx-cast overflow-hidden rounded-lg my-6"><div class="itx-term-header flex items-center gap-1.5 px-4 py-2.5 bg-[#21262D]"><span class="w-3 h-3 rounded-full bg-[#FF5F57] shrink-0"></span><span class="w-3 h-3 rounded-full bg-[#FFBD2E] shrink-0"></span><span class="w-3 h-3 rounded-full bg-[#28C840] shrink-0"></span><span class="text-xs font-mono text-[#8B949E] ml-1">{title}</span></div>{body_start}<pre><code class="nohighlight">{transcript}</code></pre>{body_end}{end}"#,
+        start = CAST_START,
+        json = json,
+        title = escape_xml(&meta.title),
+        body_start = BODY_START,
+        transcript = escape_xml(transcript),
+        body_end = BODY_END,
+        end = CAST_END,
+    )
+}
+
+pub fn split_casts(content: &str) -> Vec<ContentSegment> {
+    let mut segments = Vec::new();
+    let mut rest = content;
+    while let Some(start) = rest.find(CAST_START) {
+        let Some(end) = rest[start..].find(CAST_END).map(|end| start + end) else { break };
+        let block = &rest[start + CAST_START.len()..end];
+        let parsed = block.split_once("-->").and_then(|(json, html)| {
+            let meta = serde_json::from_str::<CastMeta>(json).ok()?;
+            let body = html.find(BODY_START)? + BODY_START.len();
+            let transcript = html.get(body..html.len().checked_sub(BODY_END.len())?)?;
+            Some(ContentSegment::Cast {
+                meta,
+                transcript: transcript.to_string(),
+            })
+        });
+        let Some(cast) = parsed else { break };
+        if start > 0 {
+            segments.push(ContentSegment::Html(rest[..start].to_string()));
+        }
+        segments.push(cast);
+        rest = &rest[end + CAST_END.len()..];
+    }
+    if !rest.is_empty() {
+        segments.push(ContentSegment::Html(rest.to_string()));
+    }
+    segments
+}
+
+// Plays a recording in place of its transcript, fetched on the first play.
+#[component]
+pub fn CastPlayer(meta: CastMeta, transcript: String) -> impl IntoView {
+    let CastMeta { src, title } = meta;
+    let requested = create_rw_signal(false);
+    let cast = create_local_resource(
+        move || requested.get(),
+        move |requested| {
+            let src = src.clone();
+            async move {
+                match requested {
+                    true => get_cast(src).await.ok(),
+                    false => None,
+                }
+            }
+        },
+    );
+    let loaded = move || cast.with(|cast| matches!(cast, Some(Some(_))));
+    let playing = create_rw_signal(false);
+    let position = create_rw_signal(0.0);
+    let duration = move || cast.with(|cast| cast.as_ref().and_then(Option::as_ref).map_or(0.0, |cast| cast.duration));
+
+    // Only re-rendered when another event comes into view, not on every frame.
+    let shown = create_memo(move |_| {
+        let position = position.get();
+        cast.with(|cast| {
+            let cast = cast.as_ref()?.as_ref()?;
+            Some(cast.events.partition_point(|(time, _)| *time <= position))
+        })
+    });
+    let screen = move || {
+        let shown = shown.get()?;
+        cast.with(|cast| {
+            let cast = cast.as_ref()?.as_ref()?;
+            let mut terminal = Terminal::new(cast.width, cast.height);
+            for (_, output) in &cast.events[..shown] {
+                terminal.write(output);
+            }
+            Some(terminal.screen())
+        })
+    };
+
+    let ticker = store_value(None::<leptos_dom::helpers::IntervalHandle>);
+    let stop = move || {
+        if let Some(handle) = ticker.get_value() {
+            handle.clear();
+        }
+        ticker.set_value(None);
+    };
+    create_effect(move |_| {
+        stop();
+        if !playing.get() {
+            return;
+        }
+        let handle = set_interval_with_handle(
+            move || {
+                let duration = duration();
+                if duration == 0.0 {
+                    return;
+                }
+                let next = position.get_untracked() + FRAME.as_secs_f64();
+                position.set(next.min(duration));
+                if next >= duration {
+                    playing.set(false);
+                }
+            },
+            FRAME,
+        );
+        ticker.set_value(handle.ok());
+    });
+    on_cleanup(stop);
+
+    let toggle = move |_| {
+        requested.set(true);
+        if !playing.get_untracked() && position.get_untracked() >= duration() && duration() > 0.0 {
+            position.set(0.0);
+        }
+        playing.update(|playing| *playing = !*playing);
+    };
+    let seek = move |ev| {
+        if let Ok(value) = event_target_value(&ev).parse::<f64>() {
+            position.set(value);
+        }
+    };
+
+    view! {
+        <div class="itx-term-block itx-cast overflow-hidden rounded-lg my-6">
+            <div class="itx-term-header flex items-center gap-1.5 px-4 py-2.5 bg-[#21262D]">
+                <span class="w-3 h-3 rounded-full bg-[#FF5F57] shrink-0"></span>
+                <span class="w-3 h-3 rounded-full bg-[#FFBD2E] shrink-0"></span>
+                <span class="w-3 h-3 rounded-full bg-[#28C840] shrink-0"></span>
+                <span class="text-xs font-mono text-[#8B949E] ml-1">{title}</span>
+            </div>
+            {move || match screen() {
+                Some(screen) => {
+                    view! {
+                        <div class="itx-term-body">
+                            <pre>
+                                <code class="nohighlight">{screen}</code>
+                            </pre>
+                        </div>
+                    }
+                        .into_view()
+                }
+                None => view! { <div class="itx-term-body" inner_html=transcript.clone()></div> }.into_view(),
+            }}
+            <div class="itx-cast-controls">
+                <button
+                    type="button"
+                    class="itx-cast-toggle"
+                    aria-label=move || if playing.get() { "Pause" } else { "Play" }
+                    on:click=toggle
+                >
+                    {move || if playing.get() { "\u{275A}\u{275A}" } else { "\u{25B6}" }}
+                </button>
+                <input
+                    type="range"
+                    class="itx-cast-seek"
+                    aria-label="Seek"
+                    min="0"
+                    step="0.1"
+                    max=move || duration().to_string()
+                    prop:value=move || position.get().to_string()
+                    disabled=move || !loaded()
+                    on:input=seek
+                />
+                <span class="itx-cast-time">
+                    {move || format!("{} / {}", clock(position.get()), clock(duration()))}
+                </span>
+            </div>
+        </div>
+    }
+}
+
+fn clock(seconds: f64) -> String {
+    let seconds = seconds.max(0.0) as u64;
+    format!("{}:{:02}", seconds / 60, seconds % 60)
+}
+
+// Just enough of a VT100 to replay shell sessions: printing, cursor movement and erasing.
+// Colours and other attributes are dropped.
+pub struct Terminal {
+    width: usize,
+    height: usize,
+    lines: Vec<Vec<char>>,
+    row: usize,
+    col: usize,
+    escape: Escape,
+}
+
+enum Escape {
+    None,
+    Start,
+    // Parameters of a `ESC [` sequence so far.
+    Csi(String),
+    // `ESC ]` sequences, e.g. window titles, ended by BEL or `ESC \`.
+    Osc { after_escape: bool },
+}
+
+impl Terminal {
+    pub fn new(width: usize, height: usize) -> Self {
+        Self {
+            width: width.max(1),
+            height: height.max(1),
+            lines: vec![Vec::new()],
+            row: 0,
+            col: 0,
+            escape: Escape::None,
+        }
+    }
+
+    // First line of the visible screen. Lines above it have scrolled out of view.
+    fn top(&self) -> usize {
+        self.lines.len().saturating_sub(self.height)
+    }
+
+    fn move_to(&mut self, row: usize, col: usize) {
+        self.row = row;
+        self.col = col.min(self.width - 1);
+        while self.lines.len() <= self.row {
+            self.lines.push(Vec::new());
+        }
+    }
+
+    pub fn write(&mut self, output: &str) {
+        for c in output.chars() {
+            match std::mem::replace(&mut self.escape, Escape::None) {
+                Escape::None => self.print(c),
+                Escape::Start => {
+                    self.escape = match c {
+                        '[' => Escape::Csi(String::new()),
+                        ']' => Escape::Osc { after_escape: false },
+                        _ => Escape::None,
+                    }
+                }
+                Escape::Csi(mut params) => {
+                    if ('@'..='~').contains(&c) {
+                        self.csi(&params, c);
+                    } else {
+                        params.push(c);
+                        self.escape = Escape::Csi(params);
+                    }
+                }
+                Escape::Osc { after_escape } => {
+                    if c != '\u{7}' && !(after_escape && c == '\\') {
+                        self.escape = Escape::Osc { after_escape: c == '\u{1b}' };
+                    }
+                }
+            }
+        }
+    }
+
+    fn print(&mut self, c: char) {
+        match c {
+            '\u{1b}' => self.escape = Escape::Start,
+            '\n' => self.move_to(self.row + 1, self.col),
+            '\r' => self.col = 0,
+            '\u{8}' => self.col = self.col.saturating_sub(1),
+            '\t' => self.col = ((self.col / 8 + 1) * 8).min(self.width - 1),
+            c if c.is_control() => {}
+            c => {
+                if self.col >= self.width {
+                    self.move_to(self.row + 1, 0);
+                }
+                let line = &mut self.lines[self.row];
+                if line.len() <= self.col {
+                    line.resize(self.col + 1, ' ');
+                }
+                line[self.col] = c;
+                self.col += 1;
+            }
+        }
+    }
+
+    fn csi(&mut self, params: &str, command: char) {
+        // Private sequences like `ESC [?25l` only change modes.
+        if params.starts_with(['?', '>', '=']) {
+            return;
+        }
+        let numbers: Vec<usize> = params.split(';').map(|n| n.parse().unwrap_or(0)).collect();
+        let arg = |i: usize, default: usize| numbers.get(i).copied().filter(|&n| n > 0).unwrap_or(default);
+        match command {
+            'A' => self.move_to(self.row.saturating_sub(arg(0, 1)).max(self.top()), self.col),
+            'B' => self.move_to(self.row + arg(0, 1), self.col),
+            'C' => self.col = (self.col + arg(0, 1)).min(self.width - 1),
+            'D' => self.col = self.col.saturating_sub(arg(0, 1)),
+            'G' => self.col = (arg(0, 1) - 1).min(self.width - 1),
+            'H' | 'f' => self.move_to(self.top() + arg(0, 1) - 1, arg(1, 1) - 1),
+            'K' => {
+                let col = self.col;
+                let line = &mut self.lines[self.row];
+                match numbers[0] {
+                    0 => line.truncate(col),
+                    1 => line.iter_mut().take(col + 1).for_each(|c| *c = ' '),
+                    _ => line.clear(),
+                }
+            }
+            // Erased lines are blanked rather than removed, so the screen doesn't scroll back.
+            'J' => match numbers[0] {
+                0 => {
+                    self.lines[self.row].truncate(self.col);
+                    self.lines[self.row + 1..].iter_mut().for_each(Vec::clear);
+                }
+                // Clearing the screen keeps what already scrolled out of view.
+                2 | 3 => {
+                    let top = self.top();
+                    self.lines[top..].iter_mut().for_each(Vec::clear);
+                    self.move_to(top, self.col);
+                }
+                _ => {}
+            },
+            _ => {}
+        }
+    }
+
+    // The visible screen, as a terminal of the recorded height would show it.
+    pub fn screen(&self) -> String {
+        join_lines(&self.lines[self.top()..])
+    }
+
+    // Everything written, including what scrolled out of view.
+    pub fn transcript(&self) -> String {
+        join_lines(&self.lines)
+    }
+}
+
+fn join_lines(lines: &[Vec<char>]) -> String {
+    let text = lines
+        .iter()
+        .map(|line| line.iter().collect::<String>().trim_end().to_string())
+        .collect::<Vec<_>>()
+        .join("\n");
+    text.trim_end().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(width: usize, height: usize, output: &str) -> Terminal {
+        let mut terminal = Terminal::new(width, height);
+        terminal.write(output);
+        terminal
+    }
+
+    #[test]
+    fn long_lines_wrap_at_the_terminal_width() {
+        assert_eq!(run(4, 5, "abcdefghij").transcript(), "abcd\nefgh\nij");
+    }
+
+    #[test]
+    fn carriage_returns_and_erasing_rewrite_the_line() {
+        assert_eq!(run(20, 5, "progress 10%\rprogress 100%\r\n").transcript(), "progress 100%");
+        assert_eq!(run(20, 5, "$ lss\u{8}\u{1b}[K\r\n").transcript(), "$ ls");
+        assert_eq!(run(20, 5, "one\r\ntwo\u{1b}[Athree").transcript(), "onethree\ntwo");
+        assert_eq!(run(20, 5, "a\nb").transcript(), "a\n b");
+    }
+
+    #[test]
+    fn escape_sequences_print_nothing() {
+        let output = "\u{1b}]0;title\u{7}\u{1b}[?25l\u{1b}[1;31mred\u{1b}[0m \u{1b}]2;other\u{1b}\\done";
+        assert_eq!(run(20, 5, output).transcript(), "red done");
+    }
+
+    #[test]
+    fn clearing_the_screen_keeps_the_scrollback() {
+        let terminal = run(10, 2, "1\r\n2\r\n3\r\n4\u{1b}[2J\u{1b}[Hnew");
+        assert_eq!(terminal.screen(), "new");
+        assert_eq!(terminal.transcript(), "1\n2\nnew");
+
+        let terminal = run(10, 2, "1\r\n2\r\n3\r\n4\u{1b}[1;1H\u{1b}[Jnew");
+        assert_eq!(terminal.screen(), "new");
+        assert_eq!(terminal.transcript(), "1\n2\nnew");
+    }
+
+    #[test]
+    fn the_screen_shows_the_last_rows() {
+        let terminal = run(10, 2, "a\r\nb\r\nc\r\n\u{1b}[1;1Htop");
+        assert_eq!(terminal.screen(), "top");
+        assert_eq!(terminal.transcript(), "a\nb\ntop");
+    }
+}
@@ -1,10 +1,24 @@
 use crate::components::footer::HomeFooter;
+use crate::config::{use_site_config, CollectionConfig};
+use crate::error_template::AppError;
+use crate::server_functions::posts::Posts;
 use http::StatusCode;
 use leptos::*;
 use leptos_meta::*;
+use leptos_router::RouterContext;
+
+// How many posts a 404 page suggests, and how close their slug has to be to the missing one.
+const MAX_SUGGESTIONS: usize = 3;
+const MIN_SIMILARITY: f64 = 0.5;
 
 #[component]
-pub fn Error(error_code: StatusCode, error_string: String) -> impl IntoView {
+pub fn Error(
+    error_code: StatusCode,
+    error_string: String,
+    // Shown under the message, e.g. links to what the reader was probably looking for.
+    #[prop(optional)] children: Option<Children>,
+) -> impl IntoView {
+    let site_name = use_site_config().name;
     view! {
         <Title text="Error" />
         <Meta name="description" content="An error occurred." />
@@ -16,7 +30,7 @@ pub fn Error(error_code: StatusCode, error_string: String) -> impl IntoView {
                         href="#"
                         aria-label="Brand"
                     >
-                        "Itehax"
+                        {site_name}
                     </a>
                 </nav>
             </header>
@@ -27,6 +41,7 @@ pub fn Error(error_code: StatusCode, error_string: String) -> impl IntoView {
                 </h1>
                 <h1 class="block text-2xl font-bold text-[#8B949E]"></h1>
                 <p class="text-gray-400">{error_string}</p>
+                {children.map(|children| children())}
                 <div class="mt-5 flex flex-col justify-center items-center gap-2 sm:flex-row sm:gap-3">
                     <a
                         class="w-full sm:w-auto inline-flex justify-center items-center gap-x-3 text-center bg-blue-600 hover:bg-blue-700 border border-transparent text-white text-sm font-medium rounded-md focus:outline-none focus:ring-2 focus:ring-blue-600 focus:ring-offset-2  transition py-3 px-4 focus:ring-offset-gray-800"
@@ -72,3 +87,144 @@ pub fn Error(error_code: StatusCode, error_string: String) -> impl IntoView {
         </div>
     }
 }
+
+// A part of a page that failed to load, e.g. the related posts under a post, in place of the
+// part rather than the whole page. The details of server errors are logged, not shown.
+#[component]
+pub fn InlineError(error: AppError, what: &'static str) -> impl IntoView {
+    let message = match &error {
+        AppError::InternalContentError(_) => {
+            leptos::logging::error!("unable to load {}: {}", what, error);
+            format!("Unable to load the {}.", what)
+        }
+        error => error.to_string(),
+    };
+    view! { <p class="text-sm text-[#8B949E]">{message}</p> }
+}
+
+// Why a post was taken down, and where its content lives now.
+#[component]
+pub fn RemovalNotice(reason: Option<String>, replaced_by: Option<String>) -> impl IntoView {
+    view! {
+        <div class="mt-5 flex flex-col gap-1">
+            {reason.map(|reason| view! { <p class="text-[#8B949E]">{reason}</p> })}
+            {replaced_by
+                .map(|href| {
+                    view! {
+                        <a class="text-[#58A6FF] hover:text-white" href=href>
+                            "Read the new version"
+                        </a>
+                    }
+                })}
+        </div>
+    }
+}
+
+// Posts whose slug is closest to the path that wasn't found, e.g. `/blog/pe-parser` suggests
+// `/blog/writing-a-pe-parser-using-rust`.
+#[component]
+pub fn PostSuggestions() -> impl IntoView {
+    let Some(posts) = use_context::<Resource<(), Result<Posts, ServerFnError>>>() else {
+        return ().into_view();
+    };
+    let path = use_context::<RouterContext>()
+        .map(|router| router.pathname().get_untracked())
+        .unwrap_or_default();
+    let collections = use_site_config().collections;
+    view! {
+        <Suspense fallback=|| ()>
+            {
+                let path = path.clone();
+                let collections = collections.clone();
+                move || {
+                    posts
+                        .get()
+                        .and_then(Result::ok)
+                        .map(|posts| suggest_posts(&path, &posts, &collections))
+                        .filter(|suggestions| !suggestions.is_empty())
+                        .map(|suggestions| {
+                            view! {
+                                <div class="mt-5 flex flex-col gap-1">
+                                    <p class="text-[#8B949E]">"Maybe you were looking for:"</p>
+                                    {suggestions
+                                        .into_iter()
+                                        .map(|(href, title)| {
+                                            view! {
+                                                <a class="text-[#58A6FF] hover:text-white" href=href>
+                                                    {title}
+                                                </a>
+                                            }
+                                        })
+                                        .collect_view()}
+                                </div>
+                            }
+                        })
+                }
+            }
+        </Suspense>
+    }
+    .into_view()
+}
+
+// Href and title of the best matching posts, best first.
+fn suggest_posts(path: &str, posts: &Posts, collections: &[CollectionConfig]) -> Vec<(String, String)> {
+    let wanted = path
+        .rsplit('/')
+        .find(|segment| !segment.is_empty())
+        .unwrap_or_default()
+        .to_lowercase()
+        .replace(['_', ' '], "-");
+    if wanted.is_empty() {
+        return Vec::new();
+    }
+
+    let mut scored: Vec<(f64, String, String)> = collections
+        .iter()
+        .filter(|collection| collection.routes)
+        .flat_map(|collection| {
+            posts.get(&collection.name).into_iter().flatten().map(|post| {
+                let slug = post.post_metadata.create_href();
+                (
+                    similarity(&wanted, &slug),
+                    collection.href(&slug),
+                    post.post_metadata.title.clone(),
+                )
+            })
+        })
+        .filter(|(score, _, _)| *score >= MIN_SIMILARITY)
+        .collect();
+    scored.sort_by(|a, b| b.0.total_cmp(&a.0).then_with(|| a.1.cmp(&b.1)));
+    scored
+        .into_iter()
+        .take(MAX_SUGGESTIONS)
+        .map(|(_, href, title)| (href, title))
+        .collect()
+}
+
+// Between 0 and 1: the better of the edit distance similarity and the share of the wanted
+// slug's words found in the candidate, so both typos and shortened slugs match.
+fn similarity(wanted: &str, slug: &str) -> f64 {
+    let longest = wanted.chars().count().max(slug.chars().count()).max(1);
+    let edits = 1.0 - levenshtein(wanted, slug) as f64 / longest as f64;
+
+    let slug_words: Vec<&str> = slug.split('-').collect();
+    let wanted_words: Vec<&str> = wanted.split('-').filter(|word| !word.is_empty()).collect();
+    let found = wanted_words.iter().filter(|word| slug_words.contains(word)).count();
+    let words = found as f64 / wanted_words.len().max(1) as f64;
+
+    edits.max(words)
+}
+
+fn levenshtein(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, a_char) in a.chars().enumerate() {
+        let mut current = vec![i + 1; b.len() + 1];
+        for (j, b_char) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(a_char != *b_char);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
@@ -1,13 +1,24 @@
-use crate::server_functions::posts::get_graph_data;
-use leptos::*;
+use crate::components::error::InlineError;
+use crate::error_template::AppError;
+use crate::escape::escape_xml;
+use crate::server_functions::graph::{get_graph_data, GraphData, LAYOUT_HEIGHT, LAYOUT_WIDTH};
+use leptos::{nonce::use_nonce, *};
 
 #[component]
 pub fn GraphView(
     #[prop(into, optional)] filter_post: String,
 ) -> impl IntoView {
-    let graph_data = create_resource(|| (), |_| async move { get_graph_data().await });
     let is_filtered = !filter_post.is_empty();
-    let filter_post = store_value(filter_post);
+    let graph_data = create_resource(
+        move || filter_post.clone(),
+        |filter_post| async move {
+            if filter_post.is_empty() {
+                get_graph_data(None, None, None, None).await
+            } else {
+                get_graph_data(None, None, Some(filter_post), Some(1)).await
+            }
+        },
+    );
 
     view! {
         <Suspense fallback=move || {
@@ -18,56 +29,25 @@ pub fn GraphView(
                     .get()
                     .map(|result| match result {
                         Ok(data) => {
-                            let fp = filter_post.get_value();
-                            let filtered = if !fp.is_empty() {
-                                let post_href = &fp;
-                                let connected_ids: std::collections::HashSet<String> = data
-                                    .edges
-                                    .iter()
-                                    .filter(|e| &e.source == post_href || &e.target == post_href)
-                                    .flat_map(|e| vec![e.source.clone(), e.target.clone()])
-                                    .collect();
-                                let nodes: Vec<_> = data
-                                    .nodes
-                                    .iter()
-                                    .filter(|n| connected_ids.contains(&n.id))
-                                    .cloned()
-                                    .collect();
-                                let node_ids: std::collections::HashSet<String> = nodes
-                                    .iter()
-                                    .map(|n| n.id.clone())
-                                    .collect();
-                                let edges: Vec<_> = data
-                                    .edges
-                                    .iter()
-                                    .filter(|e| {
-                                        node_ids.contains(&e.source) && node_ids.contains(&e.target)
-                                    })
-                                    .cloned()
-                                    .collect();
-                                crate::server_functions::posts::GraphData {
-                                    nodes,
-                                    edges,
-                                }
-                            } else {
-                                data
-                            };
-                            if is_filtered && filtered.nodes.len() < 2 {
-                                return 
-                                view! { <></> }
-                                    .into_view();
+                            if is_filtered && data.nodes.len() < 2 {
+                                return View::default();
                             }
-                            let json = serde_json::to_string(&filtered).unwrap_or_default();
-                            let graph_el = 
+                            // Inlined into a script, so a title can't close it.
+                            let json = serde_json::to_string(&data).unwrap_or_default().replace("</", "<\\/");
+                            // The layout is computed on the server, so the graph is visible
+                            // before D3 loads and takes over for dragging and highlighting.
+                            let graph_el =
                             view! {
                                 <div
                                     id="graph-container"
                                     class="relative w-full overflow-hidden"
-                                    style="height: 220px;"
+                                    style=format!("height: {}px;", LAYOUT_HEIGHT)
+                                    inner_html=render_svg(&data)
                                 ></div>
-                                <script>
+                                <script nonce=use_nonce()>
                                     {format!(
                                         r#"window.__GRAPH_DATA__ = {};
+                                        window.__GRAPH_LAYOUT__ = {{ width: {}, height: {} }};
                                         (function() {{
                                             function loadScript(src) {{
                                                 var s = document.createElement('script');
@@ -88,6 +68,8 @@ pub fn GraphView(
                                             }}
                                         }})();"#,
                                         json,
+                                        LAYOUT_WIDTH,
+                                        LAYOUT_HEIGHT,
                                     )}
                                 </script>
                             };
@@ -108,7 +90,7 @@ pub fn GraphView(
                         }
                         Err(e) => {
                             view! {
-                                <p class="text-red-400">"Error loading graph: " {e.to_string()}</p>
+                                <InlineError error=AppError::from(e) what="graph" />
                             }
                                 .into_view()
                         }
@@ -118,3 +100,66 @@ pub fn GraphView(
         </Suspense>
     }
 }
+
+// Static SVG of an already laid out graph, styled with the same variables as `/js/graph.js`.
+fn render_svg(data: &GraphData) -> String {
+    let position = |id: &str| {
+        data.nodes
+            .iter()
+            .find(|node| node.id == id)
+            .map(|node| (node.x, node.y))
+    };
+
+    let mut svg = format!(
+        r#"<svg class="itx-graph-static" width="100%" height="100%" viewBox="0 0 {} {}" preserveAspectRatio="xMidYMid meet">"#,
+        LAYOUT_WIDTH, LAYOUT_HEIGHT
+    );
+
+    svg.push_str("<g>");
+    for edge in &data.edges {
+        if let (Some((x1, y1)), Some((x2, y2))) = (position(&edge.source), position(&edge.target)) {
+            svg.push_str(&format!(
+                r#"<line x1="{}" y1="{}" x2="{}" y2="{}" stroke-width="{}" stroke-opacity="0.8" style="stroke: var(--graph-link, #4A5568)"><title>{}</title></line>"#,
+                x1,
+                y1,
+                x2,
+                y2,
+                (1.0 + edge.weight * 0.5).min(4.0),
+                escape_xml(&edge.label)
+            ));
+        }
+    }
+    svg.push_str("</g><g>");
+    for node in &data.nodes {
+        let radius = node_radius(node.degree);
+        svg.push_str(&format!(
+            r#"<text x="{}" y="{}" dy="{}" font-size="10px" text-anchor="middle" style="fill: var(--graph-label, #8B949E); pointer-events: none; font-family: 'Anonymous Pro', monospace">{}</text>"#,
+            node.x,
+            node.y,
+            -(radius + 4.0),
+            escape_xml(&short_title(&node.title))
+        ));
+        svg.push_str(&format!(
+            r#"<a href="{}"><circle cx="{}" cy="{}" r="{}" stroke-width="1.5" style="fill: var(--graph-node-fill, #58A6FF); stroke: var(--graph-node-stroke, #161B22); cursor: pointer"><title>{}</title></circle></a>"#,
+            escape_xml(&node.href),
+            node.x,
+            node.y,
+            radius,
+            escape_xml(&node.title)
+        ));
+    }
+    svg.push_str("</g></svg>");
+    svg
+}
+
+fn node_radius(degree: usize) -> f64 {
+    6.0 + degree.min(4) as f64
+}
+
+fn short_title(title: &str) -> String {
+    if title.chars().count() > 18 {
+        format!("{}\u{2026}", title.chars().take(16).collect::<String>())
+    } else {
+        title.to_string()
+    }
+}
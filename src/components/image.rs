@@ -0,0 +1,90 @@
+use leptos::*;
+use serde::{Deserialize, Serialize};
+
+// `sizes` of the images in the post listings: three cards a row on large screens, two on tablets.
+pub const CARD_IMAGE_SIZES: &str = "(min-width: 1024px) 33vw, (min-width: 640px) 50vw, 100vw";
+// `sizes` of images in the post body, which is at most 48rem wide.
+pub const POST_IMAGE_SIZES: &str = "(min-width: 768px) 768px, 100vw";
+
+// An image with resized copies in one or more formats.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ResponsiveImage {
+    // The original, for browsers that support none of the formats.
+    pub src: String,
+    pub width: u32,
+    pub height: u32,
+    // Best format first.
+    pub sources: Vec<ImageSource>,
+    // Blurred preview shown while the image loads, as a data url. Left out for images with
+    // transparency, where it would show through.
+    pub placeholder: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ImageSource {
+    pub mime: String,
+    // e.g. `/images/responsive/pe-1a2b3c4d-400.webp 400w, ...`
+    pub srcset: String,
+}
+
+impl ResponsiveImage {
+    pub fn placeholder_style(&self) -> Option<String> {
+        self.placeholder.as_ref().map(|placeholder| {
+            format!("background-image: url({}); background-size: cover; background-repeat: no-repeat", placeholder)
+        })
+    }
+
+    // Markup for images in rendered markdown. `attributes` are the other attributes of the
+    // original `<img>`, e.g. `alt="..." class="..."`, already escaped.
+    pub fn to_html(&self, attributes: &str, sizes: &str) -> String {
+        let sources = self
+            .sources
+            .iter()
+            .map(|source| format!(r#"<source type="{}" srcset="{}" sizes="{}" />"#, source.mime, source.srcset, sizes))
+            .collect::<Vec<_>>()
+            .join("");
+        let mut attributes = if attributes.is_empty() { String::new() } else { format!(" {}", attributes) };
+        if let Some(style) = self.placeholder_style().filter(|_| !attributes.contains("style=")) {
+            attributes.push_str(&format!(r#" style="{}""#, style));
+        }
+        format!(
+            r#"<picture>{}<img src="{}"{} width="{}" height="{}" loading="lazy" decoding="async" /></picture>"#,
+            sources, self.src, attributes, self.width, self.height
+        )
+    }
+}
+
+// `<img>` for `src`, offering the resized copies in `image` when there are any.
+#[component]
+pub fn ResponsiveImg(
+    src: String,
+    image: Option<ResponsiveImage>,
+    #[prop(into)] class: String,
+    sizes: &'static str,
+    #[prop(optional, into)] alt: String,
+) -> impl IntoView {
+    let Some(image) = image else {
+        return view! { <img class=class src=src alt=alt loading="lazy" decoding="async" /> }.into_view();
+    };
+    let style = image.placeholder_style();
+    view! {
+        <picture>
+            {image
+                .sources
+                .into_iter()
+                .map(|source| view! { <source type=source.mime srcset=source.srcset sizes=sizes /> })
+                .collect_view()}
+            <img
+                class=class
+                src=image.src
+                alt=alt
+                width=image.width
+                height=image.height
+                style=style
+                loading="lazy"
+                decoding="async"
+            />
+        </picture>
+    }
+    .into_view()
+}
@@ -1,5 +1,9 @@
+pub mod backlinks;
+pub mod cast;
 pub mod error;
 pub mod footer;
 pub mod graph;
+pub mod image;
 pub mod post;
+pub mod related;
 pub mod seo;
\ No newline at end of file
@@ -1,23 +1,29 @@
+use crate::components::backlinks::Backlinks;
+use crate::components::cast::{split_casts, CastPlayer, ContentSegment};
 use crate::components::footer::GoBack;
 use crate::components::footer::HomeFooter;
 use crate::components::graph::GraphView;
-use crate::components::seo::PostSeo;
+use crate::components::image::{ResponsiveImg, CARD_IMAGE_SIZES};
+use crate::components::related::RelatedPosts;
+use crate::components::seo::{word_count, PostSeo};
+use crate::config::{use_site_config, CollectionConfig, ListingTemplate};
+use crate::error_template::error_page;
 use crate::error_template::AppError;
-use crate::error_template::ErrorTemplate;
-use crate::server_functions::posts::Post;
 use crate::server_functions::posts::PostContent;
 use crate::server_functions::posts::PostMetadata;
-use crate::server_functions::posts::PostType;
+use crate::server_functions::posts::Posts;
 use crate::server_functions::posts::TocItem;
+use crate::server_functions::posts::{get_tombstones, Tombstone};
 use leptos::*;
 use leptos_meta::*;
 use leptos_router::use_params_map;
-use std::collections::HashMap;
 
 #[component]
-pub fn Post(post_type: PostType, post_description: String) -> impl IntoView {
-    let posts = use_context::<Resource<(), Result<HashMap<PostType, Vec<Post>>, ServerFnError>>>()
+pub fn Post(collection: CollectionConfig) -> impl IntoView {
+    let posts = use_context::<Resource<(), Result<Posts, ServerFnError>>>()
         .expect("unable to find context");
+    let tagline = collection.tagline.clone();
+    let collection = store_value(collection);
     view! {
         <Body class="bg-[#0D1117]" />
         <div class="max-w-[85rem] px-4 py-10 sm:px-6 lg:px-8 lg:py-14 mx-auto">
@@ -25,23 +31,24 @@ pub fn Post(post_type: PostType, post_description: String) -> impl IntoView {
                 <h2 class="text-2xl font-bold md:text-4xl md:leading-tight text-[#E6EDF3]">
                     "Posts"
                 </h2>
-                <p class="mt-1  text-[#8B949E]">{post_description}</p>
+                <p class="mt-1  text-[#8B949E]">{tagline}</p>
             </div>
             <div class="grid sm:grid-cols-2 lg:grid-cols-3 gap-8">
                 <Transition fallback=move || {
                     view! { <p>"Loading..."</p> }
                 }>
                     {move || {
+                        let collection = collection.get_value();
                         posts
                             .get()
                             .map(|posts| match posts {
                                 Ok(posts) => {
                                     posts
-                                        .get(&post_type)
-                                        .expect("Unable to read the right post_type")
+                                        .get(&collection.name)
+                                        .expect("Unable to read the right collection")
                                         .iter()
                                         .map(|post| {
-                                            if post_type == PostType::Project {
+                                            if collection.template == ListingTemplate::Links {
                                                 view! {
                                                     <LinkPostCard
                                                         post_metadata=post.post_metadata.clone()
@@ -52,20 +59,14 @@ pub fn Post(post_type: PostType, post_description: String) -> impl IntoView {
                                                 view! {
                                                     <PostCard
                                                         post_metadata=post.post_metadata.clone()
-                                                        path=post_type.to_string()
+                                                        href=collection.href(&post.post_metadata.create_href())
                                                     />
                                                 }
                                             }
                                         })
                                         .collect_view()
                                 }
-                                Err(e) => {
-
-                                    view! {
-                                        <pre class="error">"Server Error: " {e.to_string()}</pre>
-                                    }
-                                        .into_view()
-                                }
+                                Err(e) => error_page(e.into()),
                             })
                     }}
 
@@ -78,14 +79,20 @@ pub fn Post(post_type: PostType, post_description: String) -> impl IntoView {
 }
 
 #[component]
-pub fn PostCard(post_metadata: PostMetadata, path: String) -> impl IntoView {
+pub fn PostCard(post_metadata: PostMetadata, href: String) -> impl IntoView {
+    let author = use_site_config().author;
     view! {
         <a
             class="group flex flex-col h-full border transition-all duration-300 rounded-xl p-5 border-gray-700 hover:border-transparent hover:shadow-black/[.4]"
-            href=format!("/{}/{}", path, post_metadata.create_href())
+            href=href
         >
             <div class="aspect-w-16 aspect-h-11">
-                <img class="w-full object-cover rounded-xl" src=post_metadata.image_path />
+                <ResponsiveImg
+                    class="w-full h-full object-cover rounded-xl"
+                    src=post_metadata.image_path
+                    image=post_metadata.cover
+                    sizes=CARD_IMAGE_SIZES
+                />
             </div>
             <div class="my-6">
                 <h3 class="text-xl font-semibold  text-gray-300 group-hover:text-[#E6EDF3]">
@@ -116,8 +123,8 @@ pub fn PostCard(post_metadata: PostMetadata, path: String) -> impl IntoView {
                 }}
             </div>
             <div class="mt-auto flex items-center gap-x-3">
-                <img class="w-8 h-8 rounded-full" src="https://github.com/itehax.png" />
-                <h5 class="text-sm text-gray-200">"By Itehax."</h5>
+                <img class="w-8 h-8 rounded-full" src=author.avatar_url />
+                <h5 class="text-sm text-gray-200">{format!("By {}.", author.handle)}</h5>
             </div>
         </a>
     }
@@ -125,13 +132,19 @@ pub fn PostCard(post_metadata: PostMetadata, path: String) -> impl IntoView {
 
 #[component]
 pub fn LinkPostCard(post_metadata: PostMetadata, href: String) -> impl IntoView {
+    let author = use_site_config().author;
     view! {
         <a
             class="group flex flex-col h-full border transition-all duration-300 rounded-xl p-5 border-gray-700 hover:border-transparent hover:shadow-black/[.4]"
             href=href
         >
             <div class="aspect-w-16 aspect-h-11">
-                <img class="w-full object-cover rounded-xl" src=post_metadata.image_path />
+                <ResponsiveImg
+                    class="w-full h-full object-cover rounded-xl"
+                    src=post_metadata.image_path
+                    image=post_metadata.cover
+                    sizes=CARD_IMAGE_SIZES
+                />
             </div>
             <div class="my-6">
                 <h3 class="text-xl font-semibold  text-gray-300 group-hover:text-[#E6EDF3]">
@@ -162,62 +175,63 @@ pub fn LinkPostCard(post_metadata: PostMetadata, href: String) -> impl IntoView
                 }}
             </div>
             <div class="mt-auto flex items-center gap-x-3">
-                <img class="w-8 h-8 rounded-full" src="https://github.com/itehax.png" />
-                <h5 class="text-sm text-gray-200">"By Itehax."</h5>
+                <img class="w-8 h-8 rounded-full" src=author.avatar_url />
+                <h5 class="text-sm text-gray-200">{format!("By {}.", author.handle)}</h5>
             </div>
         </a>
     }
 }
 #[component]
-pub fn RenderPost(post_type: PostType) -> impl IntoView {
-    let posts = use_context::<Resource<(), Result<HashMap<PostType, Vec<Post>>, ServerFnError>>>()
+pub fn RenderPost(collection: CollectionConfig) -> impl IntoView {
+    let posts = use_context::<Resource<(), Result<Posts, ServerFnError>>>()
         .expect("unable to find context");
     let params = use_params_map();
     let post_query = move || params.with(|params| params.get("post").cloned().unwrap_or_default());
+    let collection = store_value(collection);
+    let tombstones = create_blocking_resource(|| (), |_| async move { get_tombstones().await });
 
     view! {
         <Suspense fallback=move || {
             view! { <p>"Loading..."</p> }
         }>
             {move || {
+                let collection = collection.get_value();
                 posts
                     .get()
-                    .map(|posts| match posts {
-                        Ok(posts) => {
+                    .zip(tombstones.get())
+                    .map(|(posts, tombstones)| match posts.and_then(|posts| Ok((posts, tombstones?))) {
+                        Ok((posts, tombstones)) => {
                             let post = posts
-                                .get(&post_type)
-                                .expect("Unable to read the right post_type")
+                                .get(&collection.name)
+                                .expect("Unable to read the right collection")
                                 .iter()
                                 .find(|&p| p.post_metadata.create_href() == post_query());
                             if let Some(post) = post {
-                                let post_href = format!(
-                                    "/{}/{}",
-                                    post_type,
-                                    post.post_metadata.create_href(),
-                                );
+                                let post_href = collection.href(&post.post_metadata.create_href());
                                 view! {
                                     <PostSeo
                                         post_metadata=post.post_metadata.clone()
-                                        post_type=post_type
+                                        collection=collection.clone()
+                                        word_count=word_count(&post.post_content)
                                     />
                                     <PostLayout
                                         content=post.post_content.clone()
                                         toc=post.toc.clone()
-                                        url=post_type.to_string()
+                                        url=collection.path().trim_start_matches('/').to_string()
                                         post_href=post_href
                                     />
                                 }
                                     .into_view()
+                            } else if let Some(tombstone) = tombstones
+                                .into_iter()
+                                .find(|tombstone| tombstone.href == collection.href(&post_query()))
+                            {
+                                removed_post(tombstone)
                             } else {
-                                let mut outside_errors = Errors::default();
-                                outside_errors.insert_with_default_key(AppError::NotFound);
-                                view! { <ErrorTemplate outside_errors /> }.into_view()
+                                error_page(AppError::NotFound)
                             }
                         }
-                        Err(e) => {
-                            view! { <pre class="error">"Server Error: " {e.to_string()}</pre> }
-                                .into_view()
-                        }
+                        Err(e) => error_page(e.into()),
                     })
             }}
 
@@ -225,6 +239,28 @@ pub fn RenderPost(post_type: PostType) -> impl IntoView {
     }
 }
 
+// Old url of a removed post: a permanent redirect to its replacement, or 410 Gone.
+fn removed_post(tombstone: Tombstone) -> View {
+    let Some(replaced_by) = tombstone.replaced_by else {
+        return error_page(AppError::Gone {
+            reason: tombstone.reason,
+            replaced_by: None,
+        });
+    };
+    cfg_if::cfg_if! {
+        if #[cfg(feature = "ssr")] {
+            leptos_axum::redirect(&replaced_by);
+            if let Some(response) = use_context::<leptos_axum::ResponseOptions>() {
+                response.set_status(http::StatusCode::MOVED_PERMANENTLY);
+            }
+            ().into_view()
+        } else {
+            use leptos_router::Redirect;
+            view! { <Redirect path=replaced_by /> }.into_view()
+        }
+    }
+}
+
 #[component]
 pub fn TableOfContents(toc: Vec<TocItem>) -> impl IntoView {
     let active_id = create_rw_signal(String::new());
@@ -490,17 +526,65 @@ pub fn PostLayout(
     url: String,
     #[prop(optional)] post_href: Option<String>,
 ) -> impl IntoView {
+    // Source and alt of the image shown full size, if any.
+    let lightbox = create_rw_signal(None::<(String, String)>);
+
+    // Clicking an image in the post opens it, unless it is a link or opted out with `data-no-zoom`.
+    let open_image = move |ev: ev::MouseEvent| {
+        let target = event_target::<web_sys::Element>(&ev);
+        if target.tag_name() != "IMG" || target.closest("a, [data-no-zoom]").ok().flatten().is_some() {
+            return;
+        }
+        // The original, not the resized copy the browser picked.
+        if let Some(src) = target.get_attribute("src") {
+            lightbox.set(Some((src, target.get_attribute("alt").unwrap_or_default())));
+        }
+    };
+    let close = move |_| lightbox.set(None);
+    let escape = window_event_listener(ev::keydown, move |ev| {
+        if ev.key() == "Escape" {
+            lightbox.set(None);
+        }
+    });
+    on_cleanup(move || escape.remove());
+
     view! {
         <div class="bg-[#0D1117] min-h-screen w-full overflow-x-hidden">
             <TableOfContents toc=toc />
             <div class="max-w-3xl px-4 pt-24 pb-20 sm:px-6 lg:px-8 mx-auto">
                 <div
                     class="prose prose-blog mx-auto md:prose-lg leading-relaxed prose-pre:m-0 prose-pre:rounded-none break-words"
-                    inner_html=content
-                ></div>
+                    on:click=open_image
+                >
+                    // Recordings are players; the rest of the post is plain html.
+                    {split_casts(&content)
+                        .into_iter()
+                        .map(|segment| match segment {
+                            ContentSegment::Html(html) => view! { <div inner_html=html></div> }.into_view(),
+                            ContentSegment::Cast { meta, transcript } => {
+                                view! { <CastPlayer meta transcript /> }.into_view()
+                            }
+                        })
+                        .collect_view()}
+                </div>
+                {move || {
+                    lightbox
+                        .get()
+                        .map(|(src, alt)| {
+                            view! {
+                                <div class="itx-lightbox" role="dialog" aria-modal="true" aria-label=alt.clone() on:click=close>
+                                    <img src=src alt=alt />
+                                </div>
+                            }
+                        })
+                }}
                 {post_href
                     .map(|href| {
-                        view! { <GraphView filter_post=href /> }
+                        view! {
+                            <Backlinks post_href=href.clone() />
+                            <GraphView filter_post=href.clone() />
+                            <RelatedPosts post_href=href />
+                        }
                     })}
 
             </div>
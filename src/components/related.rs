@@ -0,0 +1,57 @@
+use crate::components::error::InlineError;
+use crate::components::post::PostCard;
+use crate::error_template::AppError;
+use crate::server_functions::related::get_related_posts;
+use leptos::*;
+
+#[component]
+pub fn RelatedPosts(#[prop(into)] post_href: String) -> impl IntoView {
+    let related = create_resource(
+        move || post_href.clone(),
+        |post_href| async move { get_related_posts(post_href).await },
+    );
+
+    view! {
+        <Suspense fallback=move || {
+            view! { <p class="text-[#8B949E]">"Loading related posts..."</p> }
+        }>
+            {move || {
+                related
+                    .get()
+                    .map(|result| match result {
+                        Ok(posts) if !posts.is_empty() => {
+                            view! {
+                                <div class="mt-16">
+                                    <h3 class="text-sm font-medium text-[#8B949E] mb-4 text-center">
+                                        "Read Next"
+                                    </h3>
+                                    <div class="grid sm:grid-cols-2 lg:grid-cols-3 gap-8">
+                                        {posts
+                                            .into_iter()
+                                            .map(|related| {
+                                                view! {
+                                                    <PostCard
+                                                        post_metadata=related.post_metadata
+                                                        href=related.href
+                                                    />
+                                                }
+                                            })
+                                            .collect_view()}
+                                    </div>
+                                </div>
+                            }
+                                .into_view()
+                        }
+                        Ok(_) => View::default(),
+                        Err(e) => {
+                            view! {
+                                <InlineError error=AppError::from(e) what="related posts" />
+                            }
+                                .into_view()
+                        }
+                    })
+            }}
+
+        </Suspense>
+    }
+}
@@ -1,15 +1,19 @@
-use crate::server_functions::posts::{PostMetadata, PostType};
+use crate::config::{use_site_config, CollectionConfig, SiteConfig};
+use crate::server_functions::posts::PostMetadata;
 use leptos::*;
 use leptos_meta::*;
+use serde_json::{json, Value};
 
 #[component]
-pub fn PostSeo(post_metadata: PostMetadata, post_type: PostType) -> impl IntoView {
+pub fn PostSeo(post_metadata: PostMetadata, collection: CollectionConfig, word_count: usize) -> impl IntoView {
+    let site_config = use_site_config();
+    let author = site_config.author.name.clone();
     // use SEO fields if provided, otherwise fall back to regular fields
     let seo_title = post_metadata
         .seo_title
         .as_ref()
         .cloned()
-        .unwrap_or_else(|| format!("{} | Edoardo D'Errico", post_metadata.title));
+        .unwrap_or_else(|| format!("{} | {}", post_metadata.title, author));
 
     let seo_description = post_metadata
         .seo_description
@@ -17,22 +21,29 @@ pub fn PostSeo(post_metadata: PostMetadata, post_type: PostType) -> impl IntoVie
         .cloned()
         .unwrap_or_else(|| post_metadata.description.clone());
 
-    let seo_image = post_metadata
-        .seo_image
-        .as_ref()
-        .cloned()
-        .unwrap_or_else(|| format!("https://edoardoderrico.com{}", post_metadata.image_path));
+    let seo_image = post_metadata.seo_image.as_ref().unwrap_or(&post_metadata.image_path);
+    // Relative images are served by this site.
+    let seo_image = if seo_image.starts_with('/') {
+        site_config.absolute_url(seo_image)
+    } else {
+        seo_image.clone()
+    };
 
-    let url = format!(
-        "https://edoardoderrico.com/{}/{}",
-        post_type,
-        post_metadata.create_href()
+    let url = site_config.absolute_url(&collection.href(&post_metadata.create_href()));
+    let structured_data = post_json_ld(
+        &site_config,
+        &post_metadata,
+        &collection,
+        &url,
+        &seo_description,
+        &seo_image,
+        word_count,
     );
 
     view! {
         <Title text=seo_title.clone() />
         <Meta name="description" content=seo_description.clone() />
-        <Meta name="author" content="Edoardo D'Errico" />
+        <Meta name="author" content=author.clone() />
 
         {post_metadata
             .seo_keywords
@@ -46,11 +57,11 @@ pub fn PostSeo(post_metadata: PostMetadata, post_type: PostType) -> impl IntoVie
         <Meta property="og:description" content=seo_description.clone() />
         <Meta property="og:image" content=seo_image.clone() />
         <Meta property="article:published_time" content=post_metadata.date.clone() />
-        <Meta property="article:author" content="Edoardo D'Errico" />
+        <Meta property="article:author" content=author />
 
         // Twitter
         <Meta name="twitter:card" content="summary_large_image" />
-        <Meta property="twitter:domain" content="edoardoderrico.com" />
+        <Meta property="twitter:domain" content=site_config.domain().to_string() />
         <Meta property="twitter:url" content=url.clone() />
         <Meta name="twitter:title" content=seo_title />
         <Meta name="twitter:description" content=seo_description />
@@ -58,5 +69,147 @@ pub fn PostSeo(post_metadata: PostMetadata, post_type: PostType) -> impl IntoVie
 
         // Canonical
         <Link rel="canonical" href=url />
+
+        <JsonLd data=structured_data />
+    }
+}
+
+// Describes the author and the site, with a search box for search engines. Used by the home page.
+#[component]
+pub fn SiteJsonLd() -> impl IntoView {
+    let site_config = use_site_config();
+    let home = site_config.absolute_url("/");
+    let website = json!({
+        "@type": "WebSite",
+        "@id": format!("{}#website", home),
+        "url": home,
+        "name": site_config.name,
+        "description": site_config.description,
+        "inLanguage": "en",
+        "author": { "@id": person_id(&site_config) },
+        "potentialAction": {
+            "@type": "SearchAction",
+            "target": {
+                "@type": "EntryPoint",
+                "urlTemplate": site_config.search_url_template(),
+            },
+            "query-input": "required name=search_term_string",
+        },
+    });
+    let data = json!({
+        "@context": "https://schema.org",
+        "@graph": [person_json_ld(&site_config), website],
+    });
+    view! { <JsonLd data /> }
+}
+
+// schema.org data for rich results, inlined as `<script type="application/ld+json">`.
+#[component]
+fn JsonLd(data: Value) -> impl IntoView {
+    let json = serde_json::to_string(&data).unwrap_or_default().replace("</", "<\\/");
+    view! { <script type="application/ld+json" inner_html=json></script> }
+}
+
+// Words of the rendered post, with the html tags left out.
+pub fn word_count(html: &str) -> usize {
+    let mut text = String::with_capacity(html.len());
+    let mut in_tag = false;
+    for c in html.chars() {
+        match c {
+            '<' => {
+                in_tag = true;
+                text.push(' ');
+            }
+            '>' => in_tag = false,
+            _ if !in_tag => text.push(c),
+            _ => {}
+        }
     }
+    text.split_whitespace().count()
+}
+
+fn person_id(site_config: &SiteConfig) -> String {
+    format!("{}#person", site_config.absolute_url("/"))
+}
+
+fn person_json_ld(site_config: &SiteConfig) -> Value {
+    let social = &site_config.social;
+    let same_as: Vec<&String> = [&social.github, &social.linkedin, &social.discord, &social.youtube]
+        .into_iter()
+        .flatten()
+        .collect();
+    let mut person = json!({
+        "@type": "Person",
+        "@id": person_id(site_config),
+        "name": site_config.author.name,
+        "alternateName": site_config.author.handle,
+        "url": site_config.absolute_url("/about"),
+        "image": site_config.author.avatar_url,
+        "sameAs": same_as,
+    });
+    if let Some(email) = &social.email {
+        person["email"] = json!(format!("mailto:{}", email));
+    }
+    person
+}
+
+fn post_json_ld(
+    site_config: &SiteConfig,
+    post_metadata: &PostMetadata,
+    collection: &CollectionConfig,
+    url: &str,
+    description: &str,
+    image: &str,
+    word_count: usize,
+) -> Value {
+    let author = json!({
+        "@type": "Person",
+        "@id": person_id(site_config),
+        "name": site_config.author.name,
+        "url": site_config.absolute_url("/about"),
+    });
+    let mut post = json!({
+        "@type": collection.schema_type,
+        "@id": url,
+        "url": url,
+        "mainEntityOfPage": url,
+        "headline": post_metadata.title,
+        "description": description,
+        "image": image,
+        "datePublished": post_metadata.date,
+        "dateModified": post_metadata.date,
+        "keywords": post_metadata.tags.join(", "),
+        "wordCount": word_count,
+        "inLanguage": "en",
+        "author": author,
+        "publisher": author,
+        "isPartOf": { "@id": format!("{}#website", site_config.absolute_url("/")) },
+    });
+    if let Some(series) = &post_metadata.series {
+        post["isPartOf"] = json!([post["isPartOf"], { "@type": "CreativeWorkSeries", "name": series }]);
+    }
+    if collection.schema_type == "SoftwareSourceCode" && post_metadata.project_link != "none" {
+        post["codeRepository"] = json!(post_metadata.project_link);
+    }
+
+    // The section name is the first part of the listing title, e.g. `Blog | ...` -> Blog.
+    let section = collection
+        .title
+        .split(" | ")
+        .next()
+        .filter(|name| !name.is_empty())
+        .unwrap_or(&collection.name);
+    let breadcrumbs = json!({
+        "@type": "BreadcrumbList",
+        "itemListElement": [
+            { "@type": "ListItem", "position": 1, "name": site_config.name, "item": site_config.absolute_url("/") },
+            { "@type": "ListItem", "position": 2, "name": section, "item": site_config.absolute_url(&collection.path()) },
+            { "@type": "ListItem", "position": 3, "name": post_metadata.title, "item": url },
+        ],
+    });
+
+    json!({
+        "@context": "https://schema.org",
+        "@graph": [post, breadcrumbs],
+    })
 }
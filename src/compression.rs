@@ -0,0 +1,41 @@
+use axum::http::{header::CONTENT_TYPE, Extensions, HeaderMap, StatusCode, Version};
+use tower_http::compression::{
+    predicate::{Predicate, SizeAbove},
+    CompressionLayer,
+};
+
+// Responses smaller than this gain nothing from compression.
+const MIN_SIZE: u16 = 512;
+
+// Text formats produced by the server: pages, server function payloads, the feed and the
+// graph exports. Images and fonts are already compressed.
+const COMPRESSIBLE_TYPES: [&str; 10] = [
+    "text/html",
+    "text/css",
+    "text/plain",
+    "text/vnd.graphviz",
+    "text/javascript",
+    "application/javascript",
+    "application/json",
+    "application/ld+json",
+    "application/xml",
+    "application/graphml+xml",
+];
+
+// Brotli, gzip or zstd depending on `Accept-Encoding`. Static files that were precompressed at
+// build time are served as is by `ServeDir`, since they already carry a `Content-Encoding`.
+pub fn compression_layer() -> CompressionLayer<impl Predicate> {
+    CompressionLayer::new()
+        .br(true)
+        .gzip(true)
+        .zstd(true)
+        .compress_when(SizeAbove::new(MIN_SIZE).and(is_compressible))
+}
+
+fn is_compressible(_: StatusCode, _: Version, headers: &HeaderMap, _: &Extensions) -> bool {
+    headers
+        .get(CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.split(';').next())
+        .is_some_and(|content_type| COMPRESSIBLE_TYPES.contains(&content_type.trim()))
+}
@@ -0,0 +1,594 @@
+use leptos::*;
+use serde::{Deserialize, Serialize};
+
+// Site-wide settings, loaded at startup from `site.toml` (or the file in `SITE_CONFIG`)
+// and then overridden by `SITE_*` environment variables.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(default)]
+pub struct SiteConfig {
+    pub name: String,
+    pub base_url: String,
+    pub description: String,
+    // What the site is about in a few words, used in the home and about page titles.
+    pub tagline: String,
+    // Search page advertised to search engines, with `{search_term_string}` where the query
+    // goes. Defaults to a web search restricted to the site's domain.
+    pub search_url: Option<String>,
+    // Source files that posts embed with `{{#include path/to/file.rs:10:40}}`. Include paths
+    // are relative to it and can't leave it.
+    #[serde(skip_serializing)]
+    pub snippets_dir: String,
+    pub author: AuthorConfig,
+    pub social: SocialLinks,
+    // Sections of the site, in the order they appear in the navigation and the feed.
+    pub collections: Vec<CollectionConfig>,
+    pub cache: CacheConfig,
+    pub security: SecurityConfig,
+    // Only used by the server, and left out of the json embedded in the page.
+    #[serde(skip_serializing)]
+    pub server: ServerConfig,
+    #[serde(skip_serializing)]
+    pub analytics: AnalyticsConfig,
+    #[serde(skip_serializing)]
+    pub rate_limit: RateLimitConfig,
+    #[serde(skip_serializing)]
+    pub images: ImagesConfig,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(default)]
+pub struct AuthorConfig {
+    pub name: String,
+    pub handle: String,
+    pub avatar_url: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+#[serde(default)]
+pub struct SocialLinks {
+    pub github: Option<String>,
+    pub linkedin: Option<String>,
+    pub discord: Option<String>,
+    pub youtube: Option<String>,
+    pub email: Option<String>,
+}
+
+// Cache-Control lifetimes, in seconds. Fingerprinted assets are always cached for a year.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(default)]
+pub struct CacheConfig {
+    pub html_max_age: u64,
+    pub feed_max_age: u64,
+}
+
+// Content-Security-Policy and Strict-Transport-Security settings.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(default)]
+pub struct SecurityConfig {
+    // Sends `Content-Security-Policy-Report-Only`, so violations are reported but not blocked.
+    pub csp_report_only: bool,
+    pub hsts_max_age: u64,
+}
+
+// How the server listens and shuts down. The TCP address comes from `LEPTOS_SITE_ADDR`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(default)]
+pub struct ServerConfig {
+    // Listen on this Unix domain socket instead of the TCP address.
+    pub unix_socket: Option<String>,
+    // PEM files; when both are set the server terminates TLS itself. The files are watched
+    // and reloaded when they change.
+    pub tls_cert: Option<String>,
+    pub tls_key: Option<String>,
+    // Seconds in-flight requests get to finish after SIGTERM/SIGINT.
+    pub drain_timeout: u64,
+    // Addresses or CIDR ranges of reverse proxies whose `X-Forwarded-*` headers are trusted.
+    // Connections over the Unix socket always are.
+    pub trusted_proxies: Vec<String>,
+    // 301s requests for other host names (e.g. `www.`) and paths with a trailing slash to the
+    // canonical url built from `base_url`. Addresses and localhost are left alone.
+    pub canonical_redirects: bool,
+}
+
+// First-party page view counting. Nothing is stored that identifies a visitor: addresses are
+// only used for the country lookup and a hash that changes every day.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(default)]
+pub struct AnalyticsConfig {
+    pub enabled: bool,
+    // Append-only file the views are written to, one json object per line.
+    pub path: String,
+    // MaxMind GeoLite2 Country database, used when the file exists.
+    pub geoip_db: String,
+    // Random secret the daily visitor hash key is derived from, created on first start. It
+    // keeps visitors countable across restarts within a day.
+    pub secret_path: String,
+    // Password for `/admin/stats` (user `admin`). The page is disabled while unset; it is
+    // meant to come from `SITE_ADMIN_PASSWORD` rather than the config file.
+    pub admin_password: Option<String>,
+}
+
+// Per client token buckets, one set for server functions and one for pages and exports.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(default)]
+pub struct RateLimitConfig {
+    pub enabled: bool,
+    pub api: RateLimit,
+    pub pages: RateLimit,
+    // Largest request body accepted by `/api`, in bytes.
+    pub api_body_limit: usize,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct RateLimit {
+    // Requests a client can make at once.
+    pub burst: u32,
+    // How fast the allowance refills.
+    pub per_second: f64,
+}
+
+// Resized copies of covers and inline post images, so browsers download the smallest file
+// that fills the layout.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(default)]
+pub struct ImagesConfig {
+    pub enabled: bool,
+    // Widths in pixels. Images are never scaled up, so narrower ones get fewer variants.
+    pub widths: Vec<u32>,
+    // Best first, as offered to the browser.
+    pub formats: Vec<ImageFormat>,
+    // 0-100, for both formats.
+    pub quality: u8,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum ImageFormat {
+    Avif,
+    Webp,
+}
+
+impl ImageFormat {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ImageFormat::Avif => "avif",
+            ImageFormat::Webp => "webp",
+        }
+    }
+
+    pub fn mime(&self) -> &'static str {
+        match self {
+            ImageFormat::Avif => "image/avif",
+            ImageFormat::Webp => "image/webp",
+        }
+    }
+}
+
+// A directory of markdown posts served under its own url prefix, e.g. `posts/blog` at `/blog`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(default)]
+pub struct CollectionConfig {
+    pub name: String,
+    pub dir: String,
+    // Defaults to `/<name>` when empty.
+    pub url_prefix: String,
+    // Link shown in the home page navigation, if any.
+    pub nav_label: Option<String>,
+    pub title: String,
+    pub description: String,
+    // Short text under the heading of the listing page.
+    pub tagline: String,
+    pub keywords: Option<String>,
+    pub template: ListingTemplate,
+    pub sort: SortOrder,
+    // schema.org type used when describing the posts, e.g. `BlogPosting`.
+    pub schema_type: String,
+    // Whether the listing and post pages are served at all.
+    pub routes: bool,
+    pub in_feed: bool,
+    pub in_graph: bool,
+    // Whether the posts can be found through related posts and other lookups across the site.
+    pub in_search: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum ListingTemplate {
+    // Cards linking to the rendered post.
+    #[default]
+    Cards,
+    // Cards linking to the `project_link` of each post.
+    Links,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum SortOrder {
+    #[default]
+    Newest,
+    Oldest,
+    Title,
+}
+
+impl Default for SiteConfig {
+    fn default() -> Self {
+        Self {
+            name: "Itehax".to_string(),
+            base_url: "https://itehax.com".to_string(),
+            description: "Itehax website, coding, hacking, reading and much more.".to_string(),
+            tagline: "Cybersecurity & CTF Writeups".to_string(),
+            search_url: None,
+            snippets_dir: "snippets".to_string(),
+            author: AuthorConfig::default(),
+            social: SocialLinks::default(),
+            collections: default_collections(),
+            cache: CacheConfig::default(),
+            security: SecurityConfig::default(),
+            server: ServerConfig::default(),
+            analytics: AnalyticsConfig::default(),
+            rate_limit: RateLimitConfig::default(),
+            images: ImagesConfig::default(),
+        }
+    }
+}
+
+impl Default for CacheConfig {
+    fn default() -> Self {
+        Self {
+            html_max_age: 300,
+            feed_max_age: 3600,
+        }
+    }
+}
+
+impl Default for SecurityConfig {
+    fn default() -> Self {
+        Self {
+            csp_report_only: false,
+            hsts_max_age: 31536000,
+        }
+    }
+}
+
+impl Default for ServerConfig {
+    fn default() -> Self {
+        Self {
+            unix_socket: None,
+            tls_cert: None,
+            tls_key: None,
+            drain_timeout: 25,
+            trusted_proxies: Vec::new(),
+            canonical_redirects: true,
+        }
+    }
+}
+
+impl Default for AnalyticsConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            path: "data/analytics.jsonl".to_string(),
+            geoip_db: "data/GeoLite2-Country.mmdb".to_string(),
+            secret_path: "data/analytics.secret".to_string(),
+            admin_password: None,
+        }
+    }
+}
+
+impl Default for ImagesConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            widths: vec![400, 800, 1200],
+            formats: vec![ImageFormat::Avif, ImageFormat::Webp],
+            quality: 70,
+        }
+    }
+}
+
+impl Default for RateLimitConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            api: RateLimit {
+                burst: 30,
+                per_second: 2.0,
+            },
+            pages: RateLimit {
+                burst: 60,
+                per_second: 5.0,
+            },
+            api_body_limit: 64 * 1024,
+        }
+    }
+}
+
+impl Default for AuthorConfig {
+    fn default() -> Self {
+        Self {
+            name: "Edoardo D'Errico".to_string(),
+            handle: "Itehax".to_string(),
+            avatar_url: "https://github.com/itehax.png".to_string(),
+        }
+    }
+}
+
+impl Default for CollectionConfig {
+    fn default() -> Self {
+        Self {
+            name: String::new(),
+            dir: String::new(),
+            url_prefix: String::new(),
+            nav_label: None,
+            title: String::new(),
+            description: String::new(),
+            tagline: String::new(),
+            keywords: None,
+            template: ListingTemplate::default(),
+            sort: SortOrder::default(),
+            schema_type: "CreativeWork".to_string(),
+            routes: true,
+            in_feed: true,
+            in_graph: true,
+            in_search: true,
+        }
+    }
+}
+
+// The sections the site shipped with before collections were configurable.
+fn default_collections() -> Vec<CollectionConfig> {
+    vec![
+        CollectionConfig {
+            name: "blog".to_string(),
+            dir: "posts/blog".to_string(),
+            nav_label: Some("Blog".to_string()),
+            title: "Blog | Cybersecurity & CTF Writeups by Edoardo D'Errico".to_string(),
+            description: "Read technical writeups on cryptography challenges, CTF solutions, exploit development, and security research. Deep dives into real-world cybersecurity problems.".to_string(),
+            tagline: "Posts about tutorial and explanation of projects i built.".to_string(),
+            schema_type: "BlogPosting".to_string(),
+            ..Default::default()
+        },
+        CollectionConfig {
+            name: "projects".to_string(),
+            dir: "posts/projects".to_string(),
+            title: "Projects | Edoardo D'Errico - Cybersecurity Engineer & Developer".to_string(),
+            description: "Portfolio of cybersecurity projects, CTF challenges, and security tools by Edoardo D'Errico. Expertise in cryptography, exploit development, and secure system design.".to_string(),
+            tagline: "Posts about projects i created.".to_string(),
+            keywords: Some("cybersecurity engineer, security researcher, CTF, cryptography, exploit development, penetration testing, security tools, portfolio".to_string()),
+            template: ListingTemplate::Links,
+            schema_type: "SoftwareSourceCode".to_string(),
+            routes: false,
+            in_graph: false,
+            in_search: false,
+            ..Default::default()
+        },
+        CollectionConfig {
+            name: "writing".to_string(),
+            dir: "posts/writing".to_string(),
+            nav_label: Some("R/W".to_string()),
+            title: "Writing | Thoughts on Technology & Security by Edoardo D'Errico".to_string(),
+            description: "Personal thoughts, reflections, and insights on cybersecurity, technology, coding, and creative problem-solving. Unfiltered perspectives from Edoardo D'Errico.".to_string(),
+            tagline: "Posts about stuff i wrote.".to_string(),
+            ..Default::default()
+        },
+    ]
+}
+
+impl CollectionConfig {
+    // Url of the listing page, e.g. `/blog`.
+    pub fn path(&self) -> String {
+        if self.url_prefix.is_empty() {
+            format!("/{}", self.name)
+        } else {
+            format!("/{}", self.url_prefix.trim_matches('/'))
+        }
+    }
+
+    pub fn href(&self, slug: &str) -> String {
+        format!("{}/{}", self.path(), slug)
+    }
+}
+
+impl SiteConfig {
+    // Scheme and host every absolute url is built from, e.g. `https://itehax.com`.
+    pub fn origin(&self) -> &str {
+        self.base_url.trim_end_matches('/')
+    }
+
+    // `path` is expected to start with a slash, e.g. `/blog`.
+    pub fn absolute_url(&self, path: &str) -> String {
+        format!("{}{}", self.origin(), path)
+    }
+
+    pub fn collection(&self, name: &str) -> Option<&CollectionConfig> {
+        self.collections.iter().find(|collection| collection.name == name)
+    }
+
+    // Collection serving `href`, e.g. `/blog/some-post` -> blog.
+    pub fn collection_for_href(&self, href: &str) -> Option<&CollectionConfig> {
+        let (prefix, _) = href.rsplit_once('/')?;
+        self.collections.iter().find(|collection| collection.path() == prefix)
+    }
+
+    // Url template of the site search, see `search_url`.
+    pub fn search_url_template(&self) -> String {
+        self.search_url.clone().unwrap_or_else(|| {
+            format!("https://duckduckgo.com/?q=site%3A{}+{{search_term_string}}", self.domain())
+        })
+    }
+
+    // Host part of the base url, as used by `twitter:domain`.
+    pub fn domain(&self) -> &str {
+        let url = self.origin();
+        url.split_once("://").map_or(url, |(_, host)| host)
+    }
+}
+
+// Id of the element the server embeds the config in, so the browser hydrates with the same values.
+pub const SITE_CONFIG_ELEMENT_ID: &str = "site-config";
+
+// Makes the config available to every component. On the server it comes from the context set up
+// by the axum handlers; in the browser it is read back from the json embedded in the page.
+pub fn provide_site_config() -> SiteConfig {
+    let config = use_context::<SiteConfig>().unwrap_or_else(config_from_document);
+    provide_context(config.clone());
+    config
+}
+
+pub fn use_site_config() -> SiteConfig {
+    use_context::<SiteConfig>().unwrap_or_default()
+}
+
+// Json for the `<script type="application/json">` tag, safe to inline into html.
+pub fn site_config_json(config: &SiteConfig) -> String {
+    serde_json::to_string(config)
+        .unwrap_or_default()
+        .replace("</", "<\\/")
+}
+
+#[cfg(feature = "hydrate")]
+fn config_from_document() -> SiteConfig {
+    document()
+        .get_element_by_id(SITE_CONFIG_ELEMENT_ID)
+        .and_then(|element| element.text_content())
+        .and_then(|json| serde_json::from_str(&json).ok())
+        .unwrap_or_default()
+}
+
+#[cfg(all(feature = "ssr", not(feature = "hydrate")))]
+fn config_from_document() -> SiteConfig {
+    site_config().clone()
+}
+
+#[cfg(not(any(feature = "ssr", feature = "hydrate")))]
+fn config_from_document() -> SiteConfig {
+    SiteConfig::default()
+}
+
+cfg_if::cfg_if! {
+    if #[cfg(feature = "ssr")] {
+        use std::sync::OnceLock;
+        use thiserror::Error;
+
+        const DEFAULT_CONFIG_PATH: &str = "site.toml";
+
+        static SITE_CONFIG: OnceLock<SiteConfig> = OnceLock::new();
+
+        #[derive(Debug, Error)]
+        pub enum ConfigError {
+            #[error("unable to read {0}: {1}")]
+            Read(String, std::io::Error),
+            #[error("invalid config in {0}: {1}")]
+            Parse(String, toml::de::Error),
+            #[error("running on Fly.io without trusted_proxies: every client would share the proxy's address, set SITE_TRUSTED_PROXIES")]
+            UntrustedFlyProxy,
+        }
+
+        impl SiteConfig {
+            // A missing config file is not an error: the defaults and env overrides are used.
+            pub fn load() -> Result<Self, ConfigError> {
+                let path = std::env::var("SITE_CONFIG").unwrap_or_else(|_| DEFAULT_CONFIG_PATH.to_string());
+                let mut config = match std::fs::read_to_string(&path) {
+                    Ok(content) => toml::from_str(&content).map_err(|e| ConfigError::Parse(path, e))?,
+                    Err(e) if e.kind() == std::io::ErrorKind::NotFound => SiteConfig::default(),
+                    Err(e) => return Err(ConfigError::Read(path, e)),
+                };
+                config.apply_env_overrides();
+                // Fly's proxy makes every connection, so without trusting it all visitors would
+                // share one rate limit bucket and one analytics visitor.
+                let on_fly = std::env::var_os("FLY_APP_NAME").is_some();
+                if on_fly && config.server.unix_socket.is_none() && config.server.trusted_proxies.is_empty() {
+                    return Err(ConfigError::UntrustedFlyProxy);
+                }
+                Ok(config)
+            }
+
+            fn apply_env_overrides(&mut self) {
+                let set = |field: &mut String, key: &str| {
+                    if let Ok(value) = std::env::var(key) {
+                        *field = value;
+                    }
+                };
+                set(&mut self.name, "SITE_NAME");
+                set(&mut self.base_url, "SITE_BASE_URL");
+                set(&mut self.description, "SITE_DESCRIPTION");
+                set(&mut self.tagline, "SITE_TAGLINE");
+                set(&mut self.snippets_dir, "SITE_SNIPPETS_DIR");
+                set(&mut self.author.name, "SITE_AUTHOR_NAME");
+                set(&mut self.author.handle, "SITE_AUTHOR_HANDLE");
+                set(&mut self.author.avatar_url, "SITE_AUTHOR_AVATAR_URL");
+                set(&mut self.analytics.path, "SITE_ANALYTICS_PATH");
+                set(&mut self.analytics.geoip_db, "SITE_GEOIP_DB");
+                set(&mut self.analytics.secret_path, "SITE_ANALYTICS_SECRET_PATH");
+                // e.g. SITE_COLLECTION_READING_LIST_DIR for the `reading-list` collection.
+                for collection in &mut self.collections {
+                    let key = collection.name.to_uppercase().replace('-', "_");
+                    set(&mut collection.dir, &format!("SITE_COLLECTION_{}_DIR", key));
+                }
+
+                let set_number = |field: &mut u64, key: &str| {
+                    if let Some(value) = std::env::var(key).ok().and_then(|value| value.parse().ok()) {
+                        *field = value;
+                    }
+                };
+                set_number(&mut self.cache.html_max_age, "SITE_CACHE_HTML_MAX_AGE");
+                set_number(&mut self.cache.feed_max_age, "SITE_CACHE_FEED_MAX_AGE");
+                set_number(&mut self.security.hsts_max_age, "SITE_HSTS_MAX_AGE");
+                set_number(&mut self.server.drain_timeout, "SITE_DRAIN_TIMEOUT");
+
+                let set_bool = |field: &mut bool, key: &str| {
+                    if let Some(value) = std::env::var(key).ok().and_then(|value| value.parse().ok()) {
+                        *field = value;
+                    }
+                };
+                set_bool(&mut self.security.csp_report_only, "SITE_CSP_REPORT_ONLY");
+                set_bool(&mut self.analytics.enabled, "SITE_ANALYTICS_ENABLED");
+                set_bool(&mut self.rate_limit.enabled, "SITE_RATE_LIMIT_ENABLED");
+                set_bool(&mut self.images.enabled, "SITE_IMAGES_ENABLED");
+                set_bool(&mut self.server.canonical_redirects, "SITE_CANONICAL_REDIRECTS");
+
+                let set_optional = |field: &mut Option<String>, key: &str| {
+                    if let Ok(value) = std::env::var(key) {
+                        *field = Some(value).filter(|value| !value.is_empty());
+                    }
+                };
+                set_optional(&mut self.search_url, "SITE_SEARCH_URL");
+                set_optional(&mut self.social.github, "SITE_SOCIAL_GITHUB");
+                set_optional(&mut self.social.linkedin, "SITE_SOCIAL_LINKEDIN");
+                set_optional(&mut self.social.discord, "SITE_SOCIAL_DISCORD");
+                set_optional(&mut self.social.youtube, "SITE_SOCIAL_YOUTUBE");
+                set_optional(&mut self.social.email, "SITE_SOCIAL_EMAIL");
+                set_optional(&mut self.server.unix_socket, "SITE_UNIX_SOCKET");
+                set_optional(&mut self.server.tls_cert, "SITE_TLS_CERT");
+                set_optional(&mut self.server.tls_key, "SITE_TLS_KEY");
+                set_optional(&mut self.analytics.admin_password, "SITE_ADMIN_PASSWORD");
+
+                // Comma separated, e.g. SITE_TRUSTED_PROXIES=127.0.0.1,10.0.0.0/8
+                if let Ok(value) = std::env::var("SITE_TRUSTED_PROXIES") {
+                    self.server.trusted_proxies = value
+                        .split(',')
+                        .map(|proxy| proxy.trim().to_string())
+                        .filter(|proxy| !proxy.is_empty())
+                        .collect();
+                }
+            }
+        }
+
+        // Called once from `main`; code running outside of a request (post loading, feeds,
+        // route generation) reads the config through `site_config`.
+        pub fn init_site_config(config: SiteConfig) -> &'static SiteConfig {
+            SITE_CONFIG.get_or_init(|| config)
+        }
+
+        pub fn site_config() -> &'static SiteConfig {
+            SITE_CONFIG.get_or_init(SiteConfig::default)
+        }
+
+        // Mirrors the leptos env: anything but an explicit production setting is dev.
+        pub fn is_dev_mode() -> bool {
+            let leptos_env = std::env::var("LEPTOS_ENV").unwrap_or_default().to_lowercase();
+            let app_env = std::env::var("APP_ENVIRONMENT").unwrap_or_default().to_lowercase();
+            !matches!(leptos_env.as_str(), "prod" | "production") && app_env != "production"
+        }
+    }
+}
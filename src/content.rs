@@ -0,0 +1,140 @@
+use crate::config::site_config;
+use crate::images;
+use crate::server_functions::backlinks::{build_backlinks, Backlink};
+use crate::server_functions::cast::PUBLIC_DIR;
+use crate::server_functions::graph::{build_graph, GraphData};
+use crate::server_functions::posts::{load_posts, PostIndex, Posts};
+use crate::server_functions::related::RelatedModel;
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    collections::HashMap,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex, PoisonError,
+    },
+    time::{Duration, Instant},
+};
+use xxhash_rust::xxh3::Xxh3;
+
+// Files are checked for changes at most this often, so a burst of requests reads them once.
+const CHECK_INTERVAL: Duration = Duration::from_secs(1);
+
+static CONTENT: Mutex<Option<Cached>> = Mutex::new(None);
+// Hash of the content last built, readable without waiting for a rebuild.
+static CURRENT_HASH: AtomicU64 = AtomicU64::new(0);
+
+struct Cached {
+    checked: Instant,
+    content: Arc<Content>,
+}
+
+// The posts and everything derived from them, built together from the same files so they
+// never disagree with each other.
+pub struct Content {
+    // Hash of every file the posts were built from, see `content_hash`.
+    pub hash: u64,
+    pub index: PostIndex,
+    pub posts: Posts,
+    pub related: RelatedModel,
+    // Posts linking to each href.
+    pub backlinks: HashMap<String, Vec<Backlink>>,
+    // Posts of the collections with `in_graph`, not laid out.
+    pub graph: GraphData,
+}
+
+impl Content {
+    fn build(hash: u64) -> Self {
+        crate::telemetry::metrics().record_content_reload();
+        let index = PostIndex::build();
+        let posts = load_posts(&index);
+        let related = RelatedModel::build(&posts);
+        let backlinks = build_backlinks(&posts);
+        let graph = build_graph(&posts);
+        Self {
+            hash,
+            index,
+            posts,
+            related,
+            backlinks,
+            graph,
+        }
+    }
+
+    // Broken links, includes and diagrams of every post, as `href: error`. Dev builds show
+    // them in the page, production ones only in /readyz and `--check-content`.
+    pub fn errors(&self) -> Vec<String> {
+        site_config()
+            .collections
+            .iter()
+            .flat_map(|collection| {
+                self.posts.get(&collection.name).into_iter().flatten().flat_map(move |post| {
+                    let href = collection.href(&post.post_metadata.create_href());
+                    post.content_errors.iter().map(move |error| format!("{}: {}", href, error))
+                })
+            })
+            .collect()
+    }
+}
+
+// The current content, rebuilt first when a file changed since it was last read. It reads from
+// disk, so async code calls it through `spawn_blocking`.
+pub fn content() -> Arc<Content> {
+    let mut cached = CONTENT.lock().unwrap_or_else(PoisonError::into_inner);
+    if let Some(cached) = cached.as_ref().filter(|cached| cached.checked.elapsed() < CHECK_INTERVAL) {
+        return cached.content.clone();
+    }
+
+    let hash = content_hash();
+    let content = match cached.as_ref().filter(|cached| cached.content.hash == hash) {
+        Some(cached) => cached.content.clone(),
+        None => Arc::new(Content::build(hash)),
+    };
+    CURRENT_HASH.store(hash, Ordering::Relaxed);
+    *cached = Some(Cached {
+        checked: Instant::now(),
+        content: content.clone(),
+    });
+    content
+}
+
+// Hash of the posts pages are currently rendered from, 0 before they were first read.
+pub fn current_hash() -> u64 {
+    CURRENT_HASH.load(Ordering::Relaxed)
+}
+
+// Hash of everything a rendered post depends on: the markdown files, the snippets they include,
+// the recordings they embed and the responsive images generated so far.
+fn content_hash() -> u64 {
+    let config = site_config();
+    let mut hasher = Xxh3::new();
+    hasher.update(&images::generation().to_le_bytes());
+    for collection in &config.collections {
+        hash_files(&mut hasher, Path::new(&collection.dir), &|path| has_extension(path, "md"));
+    }
+    hash_files(&mut hasher, Path::new(&config.snippets_dir), &|_| true);
+    hash_files(&mut hasher, Path::new(PUBLIC_DIR), &|path| has_extension(path, "cast"));
+    hasher.digest()
+}
+
+// Adds the path and contents of every matching file under `dir`, in a fixed order.
+fn hash_files(hasher: &mut Xxh3, dir: &Path, include: &dyn Fn(&Path) -> bool) {
+    let Ok(entries) = fs::read_dir(dir) else { return };
+    let mut paths: Vec<PathBuf> = entries.filter_map(Result::ok).map(|entry| entry.path()).collect();
+    paths.sort();
+    for path in paths {
+        if path.is_dir() {
+            hash_files(hasher, &path, include);
+        } else if include(&path) {
+            if let Ok(bytes) = fs::read(&path) {
+                hasher.update(path.to_string_lossy().as_bytes());
+                hasher.update(&(bytes.len() as u64).to_le_bytes());
+                hasher.update(&bytes);
+            }
+        }
+    }
+}
+
+fn has_extension(path: &Path, extension: &str) -> bool {
+    path.extension().is_some_and(|ext| ext == extension)
+}
@@ -0,0 +1,234 @@
+// Diagrams written as text in fenced code blocks and rendered to inline svg when posts are
+// parsed. The svg draws with `currentColor` so the page theme decides the colours.
+
+use crate::escape::escape_xml;
+
+// Width of a bytefield diagram, whatever the number of bits per row.
+const BYTEFIELD_WIDTH: f64 = 640.0;
+const BYTEFIELD_ROW_HEIGHT: f64 = 36.0;
+const BYTEFIELD_RULER_HEIGHT: f64 = 18.0;
+// Upper bound on the size of a bytefield diagram, 512 bytes, so a typo can't draw a huge svg.
+const MAX_BYTEFIELD_BITS: usize = 4096;
+
+// Renders a fenced block in one of the diagram languages, `None` for any other language.
+// `id` keeps the ids inside the svg unique when a post has several diagrams.
+pub fn render_diagram(language: &str, source: &str, id: usize) -> Option<Result<String, String>> {
+    match language {
+        "dot" => Some(render_dot(source, id)),
+        "bytefield" => Some(render_bytefield(source)),
+        _ => None,
+    }
+}
+
+// Graphviz `dot` source, laid out by layout-rs. It covers most of dot, but not subgraphs or
+// html labels.
+fn render_dot(source: &str, id: usize) -> Result<String, String> {
+    use layout::backends::svg::SVGWriter;
+    use layout::gv::{DotParser, GraphBuilder};
+
+    let graph = DotParser::new(source).process()?;
+    // The layout code panics on some graphs it doesn't support instead of returning an error.
+    let svg = std::panic::catch_unwind(|| {
+        let mut builder = GraphBuilder::new();
+        builder.visit_graph(&graph);
+        let mut visual = builder.get();
+        let mut writer = SVGWriter::new();
+        visual.do_it(false, false, false, &mut writer);
+        writer.finalize()
+    })
+    .map_err(|_| "unable to lay out the graph".to_string())?;
+
+    // The writer's own font classes would apply to the whole page, and its ids would clash
+    // between diagrams.
+    let style_re = regex::Regex::new(r"(?s)<\?xml[^>]*\?>|<style>.*?</style>").unwrap();
+    let font_re = regex::Regex::new(r#"class="a(\d+)""#).unwrap();
+    // Only ids and the references to them, so labels that happen to contain an id are kept.
+    let id_re = regex::Regex::new(r##"(id="|url\(#|href="#)"##).unwrap();
+    let svg = style_re.replace_all(&svg, "");
+    let svg = font_re.replace_all(&svg, r#"font-size="$1""#);
+    let svg = id_re.replace_all(&svg, format!("${{1}}dot{}-", id));
+    Ok(svg
+        .replace("#000000ff", "currentColor")
+        .replace("#ffffffff", "transparent")
+        .replace("context-stroke", "currentColor")
+        .replace("<svg ", r#"<svg role="img" "#))
+}
+
+// Packet and struct layouts, one field per line with its size in bits, or in bytes with a
+// `B` suffix. Fields wrap onto the next row; `*` is a variable length field, drawn dashed on
+// a row of its own.
+//
+//   bits 32
+//   16 Source port
+//   16 Destination port
+//   4B Sequence number
+//   * Options
+fn render_bytefield(source: &str) -> Result<String, String> {
+    let mut row_bits = 32;
+    let mut fields: Vec<(Option<usize>, &str)> = Vec::new();
+    for (number, line) in source.lines().enumerate().map(|(i, line)| (i + 1, line.trim())) {
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let (size, label) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+        let label = label.trim();
+        if size == "bits" {
+            row_bits = label
+                .parse()
+                .ok()
+                .filter(|&bits| (1..=128).contains(&bits))
+                .ok_or_else(|| format!("line {}: bits per row must be between 1 and 128", number))?;
+            continue;
+        }
+        let bits = match size {
+            "*" => None,
+            size => {
+                let (count, unit) = match size.strip_suffix(['B', 'b']) {
+                    Some(bytes) => (bytes, 8),
+                    None => (size, 1),
+                };
+                let count = count.parse::<usize>().ok().filter(|&count| count > 0);
+                let count = count.ok_or_else(|| format!("line {}: invalid field size `{}`", number, size))?;
+                let bits = count.checked_mul(unit).filter(|&bits| bits <= MAX_BYTEFIELD_BITS);
+                Some(bits.ok_or_else(|| format!("line {}: fields are limited to {} bits", number, MAX_BYTEFIELD_BITS))?)
+            }
+        };
+        fields.push((bits, label));
+    }
+    if fields.is_empty() {
+        return Err("no fields".to_string());
+    }
+
+    let bit_width = BYTEFIELD_WIDTH / row_bits as f64;
+    let mut shapes = String::new();
+    let mut bit = 0;
+    for (bits, label) in fields {
+        let (row, col) = (bit / row_bits, bit % row_bits);
+        match bits {
+            None => {
+                if col > 0 {
+                    shapes.push_str(&field_box(row, col, row_bits - col, 1, "", true, bit_width));
+                }
+                let row = row + usize::from(col > 0);
+                shapes.push_str(&field_box(row, 0, row_bits, 1, label, true, bit_width));
+                bit = (row + 1) * row_bits;
+            }
+            // Whole rows are drawn as one box.
+            Some(bits) if col == 0 && bits % row_bits == 0 => {
+                shapes.push_str(&field_box(row, 0, row_bits, bits / row_bits, label, false, bit_width));
+                bit += bits;
+            }
+            Some(bits) => {
+                let end = bit + bits;
+                while bit < end {
+                    let (row, col) = (bit / row_bits, bit % row_bits);
+                    let width = (row_bits - col).min(end - bit);
+                    shapes.push_str(&field_box(row, col, width, 1, label, false, bit_width));
+                    bit += width;
+                }
+            }
+        }
+        // Every field is at most the limit too, so `bit` can't overflow before this is reached.
+        if bit > MAX_BYTEFIELD_BITS {
+            return Err(format!("diagrams are limited to {} bits", MAX_BYTEFIELD_BITS));
+        }
+    }
+
+    let rows = bit.div_ceil(row_bits);
+    let height = BYTEFIELD_RULER_HEIGHT + rows as f64 * BYTEFIELD_ROW_HEIGHT + 1.0;
+    // Every bit is numbered when they fit, otherwise every eighth.
+    let step = if row_bits <= 32 { 1 } else { 8 };
+    let ruler: String = (0..row_bits)
+        .step_by(step)
+        .map(|bit| {
+            format!(
+                r#"<text x="{:.1}" y="{:.1}" font-size="10" opacity="0.7">{}</text>"#,
+                (bit as f64 + 0.5) * bit_width,
+                BYTEFIELD_RULER_HEIGHT / 2.0,
+                bit
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("");
+    Ok(format!(
+        r#"<svg role="img" width="{width}" height="{height}" viewBox="-0.5 -0.5 {width} {height}" xmlns="http://www.w3.org/2000/svg" fill="none" stroke="currentColor" text-anchor="middle" dominant-baseline="middle"><g stroke="none" fill="currentColor">{ruler}</g>{shapes}</svg>"#,
+        width = BYTEFIELD_WIDTH + 1.0,
+        height = height,
+        ruler = ruler,
+        shapes = shapes,
+    ))
+}
+
+// One box of a field, with its label centred. Variable length fields get a dashed outline.
+fn field_box(row: usize, col: usize, width: usize, rows: usize, label: &str, variable: bool, bit_width: f64) -> String {
+    let x = col as f64 * bit_width;
+    let y = BYTEFIELD_RULER_HEIGHT + row as f64 * BYTEFIELD_ROW_HEIGHT;
+    let (w, h) = (width as f64 * bit_width, rows as f64 * BYTEFIELD_ROW_HEIGHT);
+    let dashed = if variable { r#" stroke-dasharray="4 3""# } else { "" };
+    let mut shape = format!(r#"<rect x="{:.1}" y="{:.1}" width="{:.1}" height="{:.1}"{} />"#, x, y, w, h, dashed);
+    if !label.is_empty() {
+        // Roughly the width of a character; long labels get a smaller font before they overflow.
+        let font_size = if label.chars().count() as f64 * 7.5 > w { 10 } else { 13 };
+        shape.push_str(&format!(
+            r#"<text x="{:.1}" y="{:.1}" font-size="{}" stroke="none" fill="currentColor">{}</text>"#,
+            x + w / 2.0,
+            y + h / 2.0,
+            font_size,
+            escape_xml(label)
+        ));
+    }
+    shape
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rects(svg: &str) -> Vec<&str> {
+        svg.match_indices("<rect ").map(|(start, _)| &svg[start..start + svg[start..].find("/>").unwrap()]).collect()
+    }
+
+    #[test]
+    fn fields_wrap_onto_the_next_row() {
+        let svg = render_bytefield("bits 32\n16 Port\n32 Sequence").unwrap();
+        assert_eq!(
+            rects(&svg),
+            [
+                r#"<rect x="0.0" y="18.0" width="320.0" height="36.0" "#,
+                r#"<rect x="320.0" y="18.0" width="320.0" height="36.0" "#,
+                r#"<rect x="0.0" y="54.0" width="320.0" height="36.0" "#,
+            ]
+        );
+    }
+
+    #[test]
+    fn whole_rows_and_variable_fields_get_one_box() {
+        let svg = render_bytefield("bits 16\n4B Address\n8 Flags\n* Options").unwrap();
+        assert_eq!(
+            rects(&svg),
+            [
+                r#"<rect x="0.0" y="18.0" width="640.0" height="72.0" "#,
+                r#"<rect x="0.0" y="90.0" width="320.0" height="36.0" "#,
+                r#"<rect x="320.0" y="90.0" width="320.0" height="36.0" stroke-dasharray="4 3" "#,
+                r#"<rect x="0.0" y="126.0" width="640.0" height="36.0" stroke-dasharray="4 3" "#,
+            ]
+        );
+    }
+
+    #[test]
+    fn oversized_diagrams_are_rejected() {
+        assert!(render_bytefield("4097 Huge").is_err());
+        assert!(render_bytefield(&format!("{}B Overflow", usize::MAX / 4)).is_err());
+        assert!(render_bytefield(&"4B Word\n".repeat(129)).is_err());
+        assert!(render_bytefield(&"4B Word\n".repeat(128)).is_ok());
+    }
+
+    #[test]
+    fn dot_ids_are_prefixed_but_labels_are_not() {
+        let svg = render_dot(r#"digraph { a [label="startarrow"]; a -> b }"#, 3).unwrap();
+        assert!(svg.contains(r#"id="dot3-endarrow""#));
+        assert!(svg.contains("url(#dot3-endarrow)"));
+        assert!(svg.contains(">startarrow<"));
+        assert!(!svg.contains(r#"id="startarrow""#));
+    }
+}
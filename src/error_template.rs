@@ -1,19 +1,73 @@
 use http::status::StatusCode;
 use leptos::*;
 use leptos_meta::{Body, Html};
+use serde::{Deserialize, Serialize};
 use thiserror::Error;
-use crate::components::error::Error as ErrorComponent;
+use crate::components::error::{Error as ErrorComponent, PostSuggestions, RemovalNotice};
 
-#[derive(Clone, Debug, Error)]
+#[derive(Clone, Debug, Error, Serialize, Deserialize)]
 pub enum AppError {
-    #[error("Not Found")]
+    #[error("This page doesn't exist.")]
     NotFound,
+    // A post that was published and then taken down.
+    #[error("This post has been removed.")]
+    Gone { reason: Option<String>, replaced_by: Option<String> },
+    // The posts could not be loaded or rendered.
+    #[error("Unable to load the content: {0}")]
+    InternalContentError(String),
+    #[error("Too many requests, please wait a moment before trying again.")]
+    RateLimited,
+    #[error("You are not allowed to see this page.")]
+    Unauthorized,
 }
 
 impl AppError {
     pub fn status_code(&self) -> StatusCode {
         match self {
             AppError::NotFound => StatusCode::NOT_FOUND,
+            AppError::Gone { .. } => StatusCode::GONE,
+            AppError::InternalContentError(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            AppError::RateLimited => StatusCode::TOO_MANY_REQUESTS,
+            AppError::Unauthorized => StatusCode::UNAUTHORIZED,
+        }
+    }
+}
+
+// Server functions can only return strings, so errors the page should tell apart are sent as
+// their message, e.g. `ServerFnError::new(AppError::Unauthorized)`.
+impl From<ServerFnError> for AppError {
+    fn from(error: ServerFnError) -> Self {
+        match error {
+            ServerFnError::ServerError(message) if message == AppError::Unauthorized.to_string() => {
+                AppError::Unauthorized
+            }
+            error => AppError::InternalContentError(error.to_string()),
+        }
+    }
+}
+
+// Full error page for `error`, in place of the page that failed.
+pub fn error_page(error: AppError) -> View {
+    let mut outside_errors = Errors::default();
+    outside_errors.insert_with_default_key(error);
+    view! { <ErrorTemplate outside_errors /> }.into_view()
+}
+
+// Id of the element holding the error the server rendered instead of the requested route.
+pub const OUTSIDE_ERROR_ELEMENT_ID: &str = "outside-error";
+
+// Error a server handler answered with instead of the requested route, e.g. the rate limiter.
+// The server gets it from the handler's context; the browser reads it back from the page so it
+// hydrates the same error page.
+pub fn outside_error() -> Option<AppError> {
+    cfg_if::cfg_if! {
+        if #[cfg(feature = "hydrate")] {
+            document()
+                .get_element_by_id(OUTSIDE_ERROR_ELEMENT_ID)
+                .and_then(|element| element.text_content())
+                .and_then(|json| serde_json::from_str(&json).ok())
+        } else {
+            use_context::<AppError>()
         }
     }
 }
@@ -25,22 +79,27 @@ pub fn ErrorTemplate(
     #[prop(optional)] outside_errors: Option<Errors>,
     #[prop(optional)] errors: Option<RwSignal<Errors>>,
 ) -> impl IntoView {
-    let errors = match outside_errors {
-        Some(e) => create_rw_signal(e),
-        None => match errors {
-            Some(e) => e,
-            None => panic!("No Errors found and we expected errors!"),
-        },
-    };
-    // Get Errors from Signal
-    let errors = errors.get_untracked();
+    let errors = outside_errors
+        .or_else(|| errors.map(|errors| errors.get_untracked()))
+        .unwrap_or_default();
 
-    // Downcast lets us take a type that implements `std::error::Error`
-    let errors: Vec<AppError> = errors
+    // Downcast lets us take a type that implements `std::error::Error`; anything else is
+    // reported as a content error.
+    let mut errors: Vec<AppError> = errors
         .into_iter()
-        .filter_map(|(_k, v)| v.downcast_ref::<AppError>().cloned())
+        .map(|(_k, v)| {
+            v.downcast_ref::<AppError>()
+                .cloned()
+                .unwrap_or_else(|| AppError::InternalContentError(v.to_string()))
+        })
         .collect();
-    println!("Errors: {errors:#?}");
+    if errors.is_empty() {
+        errors.push(AppError::InternalContentError("unknown error".to_string()));
+    }
+    #[cfg(feature = "ssr")]
+    for error in errors.iter().filter(|error| error.status_code().is_server_error()) {
+        tracing::error!("rendering error page: {}", error);
+    }
 
     // Only the response code for the first error is actually sent from the server
     // this may be customized by the specific application
@@ -65,7 +124,23 @@ pub fn ErrorTemplate(
             children=move |error| {
                 let error_string = error.1.to_string();
                 let error_code = error.1.status_code();
-                view! { <ErrorComponent error_code=error_code error_string=error_string /> }
+                match error.1 {
+                    AppError::NotFound => {
+                        view! {
+                            <ErrorComponent error_code=error_code error_string=error_string>
+                                <PostSuggestions />
+                            </ErrorComponent>
+                        }
+                    }
+                    AppError::Gone { reason, replaced_by } => {
+                        view! {
+                            <ErrorComponent error_code=error_code error_string=error_string>
+                                <RemovalNotice reason replaced_by />
+                            </ErrorComponent>
+                        }
+                    }
+                    _ => view! { <ErrorComponent error_code=error_code error_string=error_string /> },
+                }
             }
         />
     }
@@ -0,0 +1,20 @@
+// Escapes text for xml and html, in element content and in double-quoted attributes. Used by
+// everything that writes markup as strings: svg diagrams, hexdumps, recordings and the graph
+// exports.
+pub fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn escapes_markup_and_quotes() {
+        assert_eq!(escape_xml(r#"<a href="x">&amp;</a>"#), "&lt;a href=&quot;x&quot;&gt;&amp;amp;&lt;/a&gt;");
+        assert_eq!(escape_xml("it's fine"), "it's fine");
+    }
+}
@@ -1,4 +1,5 @@
 use crate::app::App;
+use crate::state::AppState;
 use axum::response::Response as AxumResponse;
 use axum::{
     body::Body,
@@ -11,9 +12,10 @@ use tower::ServiceExt;
 use tower_http::services::ServeDir;
 
 pub async fn file_and_error_handler(
-    State(options): State<LeptosOptions>,
+    State(state): State<AppState>,
     req: Request<Body>,
 ) -> AxumResponse {
+    let options = state.leptos_options;
     let root = options.site_root.clone();
     let (parts, body) = req.into_parts();
 
@@ -32,13 +34,25 @@ pub async fn file_and_error_handler(
     if res.status() == StatusCode::OK {
         res.into_response()
     } else {
-        let handler = leptos_axum::render_app_to_stream(options.to_owned(), App);
+        let site_config = state.site_config;
+        let handler = leptos_axum::render_app_to_stream_with_context(
+            options.to_owned(),
+            move || provide_context(site_config.clone()),
+            App,
+        );
         handler(Request::from_parts(parts, body))
             .await
             .into_response()
     }
 }
 
+pub async fn server_fn_handler(State(state): State<AppState>, req: Request<Body>) -> AxumResponse {
+    let site_config = state.site_config;
+    leptos_axum::handle_server_fns_with_context(move || provide_context(site_config.clone()), req)
+        .await
+        .into_response()
+}
+
 async fn get_static_file(
     request: Request<Body>,
     root: &str,
@@ -48,6 +62,7 @@ async fn get_static_file(
     match ServeDir::new(root)
         .precompressed_gzip()
         .precompressed_br()
+        .precompressed_zstd()
         .oneshot(request)
         .await
     {
@@ -0,0 +1,228 @@
+// ```hexdump blocks, rendered as an offset / hex / ascii table whose annotated byte ranges
+// are highlighted and listed under it.
+//
+//   ```hexdump
+//   {0x00-0x01: e_magic}
+//   {0x3c-0x3f: e_lfanew}
+//   00000000  4d 5a 90 00 03 00 00 00  04 00 00 00 ff ff 00 00  |MZ..............|
+//   00000030  00 00 00 00 00 00 00 00  00 00 00 00 f0 00 00 00  |................|
+//   ```
+//
+// Lines come from `hexdump -C` or `xxd`, or are bare hex bytes that continue from the previous
+// line. The ascii column is recomputed, so it can be left out.
+
+use crate::escape::escape_xml;
+
+// Distinct highlight colours before they repeat.
+const MARK_COLORS: usize = 6;
+
+#[derive(Debug, PartialEq)]
+struct Row {
+    offset: usize,
+    bytes: Vec<u8>,
+}
+
+impl Row {
+    // Offset of the byte after the row. Rows are checked not to overflow when parsed.
+    fn end(&self) -> usize {
+        self.offset + self.bytes.len()
+    }
+}
+
+struct Annotation {
+    start: usize,
+    // Inclusive.
+    end: usize,
+    label: String,
+}
+
+pub fn render_hexdump(source: &str) -> Result<String, String> {
+    let annotation_re = regex::Regex::new(r"\{\s*([0-9a-fA-Fx]+)\s*(?:-\s*([0-9a-fA-Fx]+)\s*)?:\s*([^}]*)\}").unwrap();
+    let mut rows: Vec<Row> = Vec::new();
+    let mut annotations = Vec::new();
+    for (number, line) in source.lines().enumerate().map(|(i, line)| (i + 1, line.trim())) {
+        if line.starts_with('{') {
+            for caps in annotation_re.captures_iter(line) {
+                let start = parse_offset(&caps[1]).ok_or_else(|| format!("line {}: invalid offset `{}`", number, &caps[1]))?;
+                let end = match caps.get(2) {
+                    Some(end) => parse_offset(end.as_str()).ok_or_else(|| format!("line {}: invalid offset `{}`", number, end.as_str()))?,
+                    None => start,
+                };
+                if end < start {
+                    return Err(format!("line {}: range ends before it starts", number));
+                }
+                annotations.push(Annotation { start, end, label: caps[3].trim().to_string() });
+            }
+            continue;
+        }
+        // `*` stands for repeated lines in `hexdump -C`, which also ends with the total size
+        // alone on a line. Neither has bytes to show.
+        if line.is_empty() || line == "*" || (!line.contains(char::is_whitespace) && line.len() > 2) {
+            continue;
+        }
+        let next = rows.last().map_or(0, Row::end);
+        let row = parse_row(line, next).ok_or_else(|| format!("line {}: not a hexdump line", number))?;
+        // Every offset in the table is at most the row's end, so this is the only sum to check.
+        if row.offset.checked_add(row.bytes.len()).is_none() {
+            return Err(format!("line {}: offset out of range", number));
+        }
+        rows.push(row);
+    }
+    let Some(last) = rows.last() else { return Err("no bytes".to_string()) };
+    let size = last.end();
+    if let Some(annotation) = annotations.iter().find(|annotation| annotation.end >= size) {
+        return Err(format!("`{}` is past the end of the dump", annotation.label));
+    }
+
+    // The narrowest range wins where they overlap, so a field stands out inside its struct.
+    let mark = |offset: usize| {
+        annotations
+            .iter()
+            .enumerate()
+            .filter(|(_, annotation)| (annotation.start..=annotation.end).contains(&offset))
+            .min_by_key(|(_, annotation)| annotation.end - annotation.start)
+    };
+    let cell = |offset: usize, text: String| match mark(offset) {
+        Some((index, annotation)) => format!(
+            r#"<span class="itx-hex-mark-{}" title="{}">{}</span>"#,
+            index % MARK_COLORS + 1,
+            escape_xml(&annotation.label),
+            text
+        ),
+        None => text,
+    };
+
+    let width = rows.iter().map(|row| row.bytes.len()).max().unwrap_or(0);
+    let digits = format!("{:x}", size.saturating_sub(1)).len().max(8);
+    let mut table = String::new();
+    for row in &rows {
+        let mut hex = String::new();
+        let mut ascii = String::new();
+        for column in 0..width {
+            // Wide rows are split in groups of eight, like `hexdump -C`.
+            match column {
+                0 => {}
+                column if column % 8 == 0 => hex.push_str("  "),
+                _ => hex.push(' '),
+            }
+            let Some(&byte) = row.bytes.get(column) else {
+                hex.push_str("  ");
+                continue;
+            };
+            let offset = row.offset + column;
+            hex.push_str(&cell(offset, format!("{:02x}", byte)));
+            let printable = if byte.is_ascii_graphic() || byte == b' ' { byte as char } else { '.' };
+            ascii.push_str(&cell(offset, escape_xml(&printable.to_string())));
+        }
+        table.push_str(&format!(
+            r#"<tr><td class="itx-hex-offset">{:0digits$x}</td><td class="itx-hex-bytes">{}</td><td class="itx-hex-ascii">{}</td></tr>"#,
+            row.offset,
+            hex,
+            ascii,
+            digits = digits
+        ));
+    }
+
+    let legend = annotations
+        .iter()
+        .enumerate()
+        .map(|(index, annotation)| {
+            let range = match annotation.start == annotation.end {
+                true => format!("0x{:x}", annotation.start),
+                false => format!("0x{:x}\u{2013}0x{:x}", annotation.start, annotation.end),
+            };
+            format!(
+                r#"<li><span class="itx-hex-mark-{}">{}</span> {}</li>"#,
+                index % MARK_COLORS + 1,
+                range,
+                escape_xml(&annotation.label)
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("");
+    let legend = match legend.is_empty() {
+        true => String::new(),
+        false => format!(r#"<ul class="itx-hex-legend">{}</ul>"#, legend),
+    };
+    Ok(format!(r#"<div class="itx-hexdump"><table>{}</table>{}</div>"#, table, legend))
+}
+
+// `0x3c` is hex, anything else decimal.
+fn parse_offset(text: &str) -> Option<usize> {
+    match text.strip_prefix("0x") {
+        Some(hex) => usize::from_str_radix(hex, 16).ok(),
+        None => text.parse().ok(),
+    }
+}
+
+// An `offset  bytes  |ascii|` line. The first word is the offset when it ends with `:`, as
+// in `xxd`, or has at least eight digits, as in `hexdump -C`; otherwise the row starts at
+// `next`.
+fn parse_row(line: &str, next: usize) -> Option<Row> {
+    let line = line.split('|').next().unwrap_or_default().trim();
+    let (first, rest) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+    let (offset, hex) = if let Some(offset) = first.strip_suffix(':') {
+        // The ascii column of `xxd` has no `|`, only the two spaces before it.
+        let hex = rest.trim_start().split("  ").next().unwrap_or_default();
+        (usize::from_str_radix(offset, 16).ok()?, hex)
+    } else if first.len() >= 8 && first.chars().all(|c| c.is_ascii_hexdigit()) {
+        (usize::from_str_radix(first, 16).ok()?, rest)
+    } else {
+        (next, line)
+    };
+    let mut bytes = Vec::new();
+    // Bytes alone or in `xxd` groups; the first other word starts the ascii column.
+    for word in hex.split_whitespace() {
+        if word.len() % 2 != 0 || word.len() > 8 || !word.chars().all(|c| c.is_ascii_hexdigit()) {
+            break;
+        }
+        for pair in word.as_bytes().chunks(2) {
+            bytes.push(u8::from_str_radix(std::str::from_utf8(pair).ok()?, 16).ok()?);
+        }
+    }
+    (!bytes.is_empty()).then_some(Row { offset, bytes })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn row(offset: usize, bytes: &[u8]) -> Option<Row> {
+        Some(Row { offset, bytes: bytes.to_vec() })
+    }
+
+    #[test]
+    fn parses_hexdump_c_rows() {
+        assert_eq!(
+            parse_row("00000010  4d 5a 90 00 03 00 00 00  04 00 00 00 ff ff 00 00  |MZ..............|", 0),
+            row(0x10, &[0x4d, 0x5a, 0x90, 0x00, 0x03, 0, 0, 0, 0x04, 0, 0, 0, 0xff, 0xff, 0, 0])
+        );
+    }
+
+    #[test]
+    fn xxd_ascii_column_is_not_read_as_bytes() {
+        assert_eq!(parse_row("00000010: 6361 6665  cafe", 0), row(0x10, &[0x63, 0x61, 0x66, 0x65]));
+        assert_eq!(parse_row("00000020: 6361 66         caf", 0), row(0x20, &[0x63, 0x61, 0x66]));
+    }
+
+    #[test]
+    fn bare_bytes_continue_from_the_previous_row() {
+        assert_eq!(parse_row("4d5a 9000", 0x40), row(0x40, &[0x4d, 0x5a, 0x90, 0x00]));
+        assert_eq!(parse_row("4d 5a  MZ", 8), row(8, &[0x4d, 0x5a]));
+        assert_eq!(parse_row("hello world", 0), None);
+    }
+
+    #[test]
+    fn offsets_past_the_address_space_are_rejected() {
+        let error = render_hexdump("ffffffffffffffff: 4d5a").unwrap_err();
+        assert!(error.contains("out of range"), "{}", error);
+    }
+
+    #[test]
+    fn annotations_mark_their_bytes() {
+        let html = render_hexdump("{0x00-0x01: magic}\n00000000: 4d5a 9000  MZ..").unwrap();
+        assert_eq!(html.matches(r#"class="itx-hex-mark-1" title="magic""#).count(), 4);
+        assert!(html.contains("0x0\u{2013}0x1</span> magic"));
+        assert!(render_hexdump("{0x10: late}\n4d 5a").is_err());
+    }
+}
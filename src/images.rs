@@ -0,0 +1,221 @@
+use crate::components::image::{ImageSource, ResponsiveImage};
+use crate::config::{site_config, ImageFormat, ImagesConfig};
+use crate::server_functions::posts::{get_posts_file, read_post_content, PostMetadata};
+use image::{imageops::FilterType, DynamicImage, GenericImageView};
+use std::{
+    collections::{BTreeSet, HashMap},
+    path::Path,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        OnceLock, RwLock,
+    },
+};
+
+// Under the site root, next to the originals so they get the same long lived cache headers.
+pub const OUTPUT_DIR: &str = "images/responsive";
+
+// AVIF encoder speed, 1-10. Slower barely shrinks the files further.
+const AVIF_SPEED: u8 = 8;
+
+// Width of the preview inlined into the page while the image loads.
+const PLACEHOLDER_WIDTH: u32 = 16;
+
+static IMAGES: OnceLock<RwLock<HashMap<String, ResponsiveImage>>> = OnceLock::new();
+// Bumped whenever an image becomes ready, so posts rendered without it are parsed again.
+static GENERATION: AtomicU64 = AtomicU64::new(0);
+
+fn images() -> &'static RwLock<HashMap<String, ResponsiveImage>> {
+    IMAGES.get_or_init(Default::default)
+}
+
+// Resized copies of `src`, e.g. `/images/blog_images/pe.webp`, once they have been generated.
+pub fn responsive_image(src: &str) -> Option<ResponsiveImage> {
+    images().read().unwrap().get(src).cloned()
+}
+
+// Number of images made ready so far, part of the content hash.
+pub fn generation() -> u64 {
+    GENERATION.load(Ordering::Relaxed)
+}
+
+// Generates the missing copies in the background. Pages use the originals until an image is ready.
+pub fn init_images(site_root: &str) {
+    if !site_config().images.enabled {
+        return;
+    }
+    let site_root = site_root.to_string();
+    std::thread::spawn(move || generate_images(&site_root));
+}
+
+// Makes sure every referenced image has its copies, returning how many images were prepared.
+pub fn generate_images(site_root: &str) -> usize {
+    let config = &site_config().images;
+    let started = std::time::Instant::now();
+    let mut prepared = 0;
+    for src in referenced_images() {
+        match prepare_image(site_root, &src, config) {
+            Ok(image) => {
+                images().write().unwrap().insert(src, image);
+                GENERATION.fetch_add(1, Ordering::Relaxed);
+                prepared += 1;
+            }
+            Err(e) => tracing::warn!("unable to resize {}: {}", src, e),
+        }
+    }
+    tracing::info!(images = prepared, elapsed_ms = started.elapsed().as_millis() as u64, "responsive images ready");
+    prepared
+}
+
+// Covers and inline markdown images of every post that are served by this site.
+fn referenced_images() -> BTreeSet<String> {
+    use gray_matter::engine::YAML;
+    use gray_matter::Matter;
+
+    // `![alt](/images/...)` and `<img src="/images/...">`.
+    let inline_re = regex::Regex::new(r#"!\[[^\]]*\]\(\s*<?(/[^)\s>]+)|<img\s[^>]*?src="(/[^"]+)""#).unwrap();
+    let matter = Matter::<YAML>::new();
+    let mut sources = BTreeSet::new();
+    for collection in &site_config().collections {
+        if !Path::new(&collection.dir).exists() {
+            continue;
+        }
+        for entry in get_posts_file(&collection.dir) {
+            let Some(content) = read_post_content(entry) else { continue };
+            if let Some(parsed) = matter.parse_with_struct::<PostMetadata>(&content) {
+                sources.insert(parsed.data.image_path);
+            }
+            sources.extend(
+                inline_re
+                    .captures_iter(&content)
+                    .filter_map(|cap| cap.get(1).or_else(|| cap.get(2)))
+                    .map(|src| src.as_str().to_string()),
+            );
+        }
+    }
+    sources.retain(|src| src.starts_with('/') && is_raster(src));
+    sources
+}
+
+fn is_raster(src: &str) -> bool {
+    let extension = Path::new(src).extension().and_then(|ext| ext.to_str()).unwrap_or_default();
+    matches!(extension.to_lowercase().as_str(), "png" | "jpg" | "jpeg" | "webp" | "gif")
+}
+
+fn prepare_image(site_root: &str, src: &str, config: &ImagesConfig) -> Result<ResponsiveImage, String> {
+    let original = Path::new(site_root).join(src.trim_start_matches('/'));
+    let bytes = std::fs::read(&original).map_err(|e| e.to_string())?;
+    let (width, height) = image::ImageReader::new(std::io::Cursor::new(&bytes))
+        .with_guessed_format()
+        .map_err(|e| e.to_string())?
+        .into_dimensions()
+        .map_err(|e| e.to_string())?;
+
+    // The content hash in the name lets the copies be cached forever.
+    let stem = original.file_stem().and_then(|stem| stem.to_str()).unwrap_or("image");
+    let name = format!("{}-{:08x}", stem, xxhash_rust::xxh3::xxh3_64(&bytes) as u32);
+    let output_dir = Path::new(site_root).join(OUTPUT_DIR);
+    std::fs::create_dir_all(&output_dir).map_err(|e| e.to_string())?;
+
+    let widths = variant_widths(width, &config.widths);
+    let mut decoded: Option<DynamicImage> = None;
+    let mut sources = Vec::new();
+    for format in &config.formats {
+        let mut srcset = Vec::new();
+        for &variant_width in &widths {
+            let file_name = format!("{}-{}.{}", name, variant_width, format.extension());
+            let path = output_dir.join(&file_name);
+            if !path.exists() {
+                if decoded.is_none() {
+                    decoded = Some(image::load_from_memory(&bytes).map_err(|e| e.to_string())?);
+                }
+                let resized = resize(decoded.as_ref().unwrap(), variant_width);
+                write_variant(&resized, *format, config.quality, &path)?;
+            }
+            srcset.push(format!("/{}/{} {}w", OUTPUT_DIR, file_name, variant_width));
+        }
+        sources.push(ImageSource {
+            mime: format.mime().to_string(),
+            srcset: srcset.join(", "),
+        });
+    }
+
+    let placeholder_path = output_dir.join(format!("{}-placeholder.webp", name));
+    if !placeholder_path.exists() {
+        if decoded.is_none() {
+            decoded = Some(image::load_from_memory(&bytes).map_err(|e| e.to_string())?);
+        }
+        let resized = resize(decoded.as_ref().unwrap(), PLACEHOLDER_WIDTH.min(width));
+        write_variant(&resized, ImageFormat::Webp, 50, &placeholder_path)?;
+    }
+    let placeholder = std::fs::read(&placeholder_path).map_err(|e| e.to_string())?;
+
+    Ok(ResponsiveImage {
+        src: src.to_string(),
+        width,
+        height,
+        sources,
+        placeholder: blurred_placeholder(&placeholder, width, height),
+    })
+}
+
+// An svg that blurs the tiny webp, so the preview looks soft instead of pixelated.
+fn blurred_placeholder(webp: &[u8], width: u32, height: u32) -> Option<String> {
+    use base64::Engine;
+
+    let preview = image::load_from_memory(webp).ok()?;
+    if preview.to_rgba8().pixels().any(|pixel| pixel[3] < 255) {
+        return None;
+    }
+    let encode = |bytes: &[u8]| base64::engine::general_purpose::STANDARD.encode(bytes);
+    let svg = format!(
+        r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 {width} {height}"><filter id="b" color-interpolation-filters="sRGB"><feGaussianBlur stdDeviation="{blur}"/></filter><image filter="url(#b)" width="100%" height="100%" preserveAspectRatio="none" href="data:image/webp;base64,{data}"/></svg>"#,
+        width = width,
+        height = height,
+        blur = (width / 40).max(8),
+        data = encode(webp),
+    );
+    Some(format!("data:image/svg+xml;base64,{}", encode(svg.as_bytes())))
+}
+
+// The configured widths narrower than the image, plus the image's own width when it is
+// narrower than the widest one.
+fn variant_widths(width: u32, configured: &[u32]) -> Vec<u32> {
+    let mut widths: Vec<u32> = configured.iter().copied().filter(|&w| w > 0 && w < width).collect();
+    if configured.iter().any(|&w| w >= width) || widths.is_empty() {
+        widths.push(width);
+    }
+    widths.sort_unstable();
+    widths.dedup();
+    widths
+}
+
+fn resize(image: &DynamicImage, width: u32) -> DynamicImage {
+    if width == image.width() {
+        return image.clone();
+    }
+    let height = (image.height() as f64 * width as f64 / image.width() as f64).round().max(1.0) as u32;
+    image.resize_exact(width, height, FilterType::Lanczos3)
+}
+
+fn write_variant(image: &DynamicImage, format: ImageFormat, quality: u8, path: &Path) -> Result<(), String> {
+    let rgba = image.to_rgba8();
+    let (width, height) = image.dimensions();
+    let encoded = match format {
+        ImageFormat::Webp => webp::Encoder::from_rgba(&rgba, width, height)
+            .encode(quality.min(100) as f32)
+            .to_vec(),
+        ImageFormat::Avif => {
+            use image::ImageEncoder;
+
+            let mut buffer = Vec::new();
+            image::codecs::avif::AvifEncoder::new_with_speed_quality(&mut buffer, AVIF_SPEED, quality.clamp(1, 100))
+                .write_image(&rgba, width, height, image::ExtendedColorType::Rgba8)
+                .map_err(|e| e.to_string())?;
+            buffer
+        }
+    };
+    // Written under a temporary name first, so a half written file is never served.
+    let partial = path.with_extension("partial");
+    std::fs::write(&partial, encoded).map_err(|e| e.to_string())?;
+    std::fs::rename(&partial, path).map_err(|e| e.to_string())
+}
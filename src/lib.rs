@@ -1,11 +1,43 @@
 pub mod app;
+pub mod config;
 pub mod error_template;
+pub mod escape;
 pub mod components;
 pub mod routes;
 pub mod server_functions;
 
+#[cfg(feature = "ssr")]
+pub mod analytics;
+#[cfg(feature = "ssr")]
+pub mod build_info;
+#[cfg(feature = "ssr")]
+pub mod cache;
+#[cfg(feature = "ssr")]
+pub mod canonical;
+#[cfg(feature = "ssr")]
+pub mod compression;
+#[cfg(feature = "ssr")]
+pub mod content;
+#[cfg(feature = "ssr")]
+pub mod diagrams;
 #[cfg(feature = "ssr")]
 pub mod fileserv;
+#[cfg(feature = "ssr")]
+pub mod hexdump;
+#[cfg(feature = "ssr")]
+pub mod images;
+#[cfg(feature = "ssr")]
+pub mod proxy;
+#[cfg(feature = "ssr")]
+pub mod rate_limit;
+#[cfg(feature = "ssr")]
+pub mod security;
+#[cfg(feature = "ssr")]
+pub mod server;
+#[cfg(feature = "ssr")]
+pub mod state;
+#[cfg(feature = "ssr")]
+pub mod telemetry;
 
 #[cfg(feature = "hydrate")]
 #[wasm_bindgen::prelude::wasm_bindgen]
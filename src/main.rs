@@ -1,36 +1,111 @@
 #[cfg(feature = "ssr")]
 #[tokio::main]
 async fn main() {
-    use axum::{routing::get, routing::post, Router};
+    use axum::{extract::DefaultBodyLimit, middleware, routing::get, routing::post, Router};
     use leptos::*;
     use leptos_axum::{generate_route_list, LeptosRoutes};
+    use itehax_website::analytics::{init_analytics, record_views, require_admin};
     use itehax_website::app::*;
-    use itehax_website::fileserv::file_and_error_handler;
+    use itehax_website::build_info::build_info;
+    use itehax_website::cache::{cache_headers, init_pkg_files};
+    use itehax_website::canonical::canonical_redirect;
+    use itehax_website::compression::compression_layer;
+    use itehax_website::config::{init_site_config, SiteConfig};
+    use itehax_website::content::content;
+    use itehax_website::fileserv::{file_and_error_handler, server_fn_handler};
+    use itehax_website::images::{generate_images, init_images};
+    use itehax_website::proxy::forwarded_headers;
+    use itehax_website::rate_limit::rate_limit;
+    use itehax_website::security::{csp_report, security_headers, CSP_REPORT_PATH};
+    use itehax_website::server::serve;
+    use itehax_website::state::AppState;
+    use itehax_website::telemetry::{init_tracing, metrics_handler, observe_requests, METRICS_PATH};
+    use tower_http::limit::RequestBodyLimitLayer;
     use itehax_website::routes::feed::rss_feed;
+    use itehax_website::routes::graph_export::{graph_dot, graph_graphml, graph_json};
+    use itehax_website::routes::health::{healthz, readyz, version};
 
     // Setting get_configuration(None) means we'll be using cargo-leptos's env values
     // For deployment these variables are:
     // <https://github.com/leptos-rs/start-axum#executing-a-server-on-a-remote-machine-without-the-toolchain>
     // Alternately a file can be specified such as Some("Cargo.toml")
     // The file would need to be included with the executable when moved to deployment
+    init_tracing();
     let conf = get_configuration(None).await.unwrap();
     let leptos_options = conf.leptos_options;
     let addr = leptos_options.site_addr;
+    init_pkg_files(&leptos_options);
+    // Loaded before generating the route list, since every collection adds its own routes.
+    let site_config = init_site_config(SiteConfig::load().expect("Unable to load site config")).clone();
+    // Run at build time so the copies ship with the site instead of being made on first start.
+    if std::env::args().any(|arg| arg == "--generate-images") {
+        generate_images(&leptos_options.site_root);
+        return;
+    }
+    // Run at build time too, so a post with a broken link or include fails the deploy.
+    if std::env::args().any(|arg| arg == "--check-content") {
+        let errors = content().errors();
+        for error in &errors {
+            eprintln!("{}", error);
+        }
+        std::process::exit(i32::from(!errors.is_empty()));
+    }
+    init_images(&leptos_options.site_root);
+    let server_config = site_config.server.clone();
+    let body_limit = site_config.rate_limit.api_body_limit;
     let routes = generate_route_list(App);
+    init_analytics(&site_config.analytics, routes.iter().map(|route| route.path().to_string()));
+
+    let state = AppState {
+        leptos_options,
+        site_config: site_config.clone(),
+    };
 
     // build our application with a route
     let app = Router::new()
-        .route("/api/*fn_name", post(leptos_axum::handle_server_fns))
+        .route(
+            "/api/*fn_name",
+            post(server_fn_handler).layer(RequestBodyLimitLayer::new(body_limit)),
+        )
+        .route(
+            CSP_REPORT_PATH,
+            post(csp_report).layer(DefaultBodyLimit::max(body_limit)),
+        )
+        .route("/healthz", get(healthz))
+        .route("/readyz", get(readyz))
+        .route("/version", get(version))
+        .route(METRICS_PATH, get(metrics_handler))
         .route("/feed.xml", get(rss_feed))
-        .leptos_routes(&leptos_options, routes, App)
+        .route("/graph.dot", get(graph_dot))
+        .route("/graph.graphml", get(graph_graphml))
+        .route("/graph.json", get(graph_json))
+        .leptos_routes_with_context(
+            &state,
+            routes,
+            move || provide_context(site_config.clone()),
+            App,
+        )
         .fallback(file_and_error_handler)
-        .with_state(leptos_options);
+        .layer(middleware::from_fn_with_state(state.clone(), cache_headers))
+        // Outside the cache layer, whose 304s carry no nonce and so get no CSP.
+        .layer(middleware::from_fn_with_state(state.clone(), security_headers))
+        // Outside the cache layer; its ETags are weak, so they hold for every encoding.
+        .layer(compression_layer())
+        // Outside the cache layer, so revalidated pages answered with a 304 count as views.
+        .layer(middleware::from_fn(record_views))
+        .layer(middleware::from_fn(require_admin))
+        // Before any work is done for the request, but inside the logging and metrics.
+        .layer(middleware::from_fn_with_state(state.clone(), rate_limit))
+        .layer(middleware::from_fn(canonical_redirect))
+        // Outside every layer but the proxy one, so the logged latency covers all of them.
+        .layer(middleware::from_fn(observe_requests))
+        // Before anything else, so every layer sees the real client.
+        .layer(middleware::from_fn(forwarded_headers))
+        .with_state(state);
 
-    let listener = tokio::net::TcpListener::bind(&addr).await.unwrap();
-    logging::log!("listening on http://{}", &addr);
-    axum::serve(listener, app.into_make_service())
-        .await
-        .unwrap();
+    let build = build_info();
+    tracing::info!(commit = %build.commit, build_time = %build.build_time, "starting server");
+    serve(app, addr, &server_config).await.expect("Server error");
 }
 
 #[cfg(not(feature = "ssr"))]
@@ -0,0 +1,189 @@
+use crate::config::site_config;
+use crate::server::ConnectionInfo;
+use axum::{
+    extract::Request,
+    http::{header::HOST, HeaderMap},
+    middleware::Next,
+    response::Response,
+};
+use std::{net::IpAddr, sync::OnceLock};
+
+const X_FORWARDED_FOR: &str = "x-forwarded-for";
+const X_FORWARDED_PROTO: &str = "x-forwarded-proto";
+const X_FORWARDED_HOST: &str = "x-forwarded-host";
+
+static TRUSTED_PROXIES: OnceLock<Vec<IpRange>> = OnceLock::new();
+
+// Who made a request, once the reverse proxies in front of the server are accounted for.
+// Added to the request extensions by `forwarded_headers`.
+#[derive(Debug, Clone)]
+pub struct ClientInfo {
+    pub ip: Option<IpAddr>,
+    // `http` or `https`, as seen by the client.
+    pub scheme: String,
+    pub host: Option<String>,
+}
+
+// An address or CIDR range from `trusted_proxies`, e.g. `10.0.0.0/8`.
+#[derive(Debug, Clone, Copy)]
+struct IpRange {
+    network: IpAddr,
+    prefix: u32,
+}
+
+impl IpRange {
+    fn parse(range: &str) -> Option<Self> {
+        let (network, prefix) = match range.split_once('/') {
+            Some((network, prefix)) => (network.parse::<IpAddr>().ok()?, Some(prefix.parse().ok()?)),
+            None => (range.parse::<IpAddr>().ok()?, None),
+        };
+        let bits = if network.is_ipv4() { 32 } else { 128 };
+        let prefix = prefix.unwrap_or(bits);
+        (prefix <= bits).then_some(Self { network, prefix })
+    }
+
+    fn contains(&self, ip: IpAddr) -> bool {
+        match (self.network, ip.to_canonical()) {
+            (IpAddr::V4(network), IpAddr::V4(ip)) => {
+                let mask = u32::MAX.checked_shl(32 - self.prefix).unwrap_or(0);
+                u32::from(network) & mask == u32::from(ip) & mask
+            }
+            (IpAddr::V6(network), IpAddr::V6(ip)) => {
+                let mask = u128::MAX.checked_shl(128 - self.prefix).unwrap_or(0);
+                u128::from(network) & mask == u128::from(ip) & mask
+            }
+            _ => false,
+        }
+    }
+}
+
+fn trusted_proxies() -> &'static [IpRange] {
+    TRUSTED_PROXIES.get_or_init(|| {
+        site_config()
+            .server
+            .trusted_proxies
+            .iter()
+            .filter_map(|range| {
+                let parsed = IpRange::parse(range);
+                if parsed.is_none() {
+                    tracing::warn!("ignoring invalid trusted proxy {}", range);
+                }
+                parsed
+            })
+            .collect()
+    })
+}
+
+fn is_trusted(ip: IpAddr) -> bool {
+    trusted_proxies().iter().any(|range| range.contains(ip))
+}
+
+// Resolves the client of every request. `X-Forwarded-*` headers are only believed when the
+// connection comes from a trusted proxy (or the Unix socket), and are removed otherwise so
+// nothing further down can be fooled by them.
+pub async fn forwarded_headers(mut req: Request, next: Next) -> Response {
+    let connection = req.extensions().get::<ConnectionInfo>().copied();
+    let remote_ip = connection.and_then(|connection| connection.remote).map(|remote| remote.ip());
+    let tls = connection.is_some_and(|connection| connection.tls);
+    let via_proxy = match (connection, remote_ip) {
+        (Some(_), Some(ip)) => is_trusted(ip),
+        (Some(_), None) => true,
+        (None, _) => false,
+    };
+
+    let headers = req.headers_mut();
+    let client = if via_proxy {
+        ClientInfo {
+            ip: forwarded_for(headers).or(remote_ip),
+            scheme: first_value(headers, X_FORWARDED_PROTO)
+                .unwrap_or_else(|| scheme(tls).to_string())
+                .to_lowercase(),
+            host: first_value(headers, X_FORWARDED_HOST).or_else(|| first_value(headers, HOST.as_str())),
+        }
+    } else {
+        for name in [X_FORWARDED_FOR, X_FORWARDED_PROTO, X_FORWARDED_HOST] {
+            headers.remove(name);
+        }
+        ClientInfo {
+            ip: remote_ip,
+            scheme: scheme(tls).to_string(),
+            host: first_value(headers, HOST.as_str()),
+        }
+    };
+    req.extensions_mut().insert(client);
+    next.run(req).await
+}
+
+fn scheme(tls: bool) -> &'static str {
+    if tls {
+        "https"
+    } else {
+        "http"
+    }
+}
+
+// The right-most address that isn't one of our proxies; anything left of it was sent by the
+// client and can't be trusted.
+fn forwarded_for(headers: &HeaderMap) -> Option<IpAddr> {
+    let addresses: Vec<IpAddr> = headers
+        .get_all(X_FORWARDED_FOR)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .filter_map(|address| address.trim().parse().ok())
+        .collect();
+    addresses
+        .iter()
+        .rev()
+        .find(|ip| !is_trusted(**ip))
+        .or_else(|| addresses.first())
+        .copied()
+}
+
+fn first_value(headers: &HeaderMap, name: &str) -> Option<String> {
+    headers
+        .get(name)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.split(',').next())
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn contains(range: &str, ip: &str) -> bool {
+        IpRange::parse(range).unwrap().contains(ip.parse().unwrap())
+    }
+
+    #[test]
+    fn parses_addresses_and_cidr_ranges() {
+        assert_eq!(IpRange::parse("127.0.0.1").map(|range| range.prefix), Some(32));
+        assert_eq!(IpRange::parse("10.0.0.0/8").map(|range| range.prefix), Some(8));
+        assert_eq!(IpRange::parse("fdaa::/16").map(|range| range.prefix), Some(16));
+        assert_eq!(IpRange::parse("::1").map(|range| range.prefix), Some(128));
+        for invalid in ["", "10.0.0.0/33", "fdaa::/129", "10.0.0.0/", "10.0.0/8", "proxy", "10.0.0.0/x"] {
+            assert!(IpRange::parse(invalid).is_none(), "{}", invalid);
+        }
+    }
+
+    #[test]
+    fn matches_addresses_inside_the_range() {
+        assert!(contains("10.0.0.0/8", "10.255.1.2"));
+        assert!(!contains("10.0.0.0/8", "11.0.0.1"));
+        assert!(contains("172.16.0.0/12", "172.31.255.255"));
+        assert!(!contains("172.16.0.0/12", "172.32.0.1"));
+        assert!(contains("127.0.0.1", "127.0.0.1"));
+        assert!(!contains("127.0.0.1", "127.0.0.2"));
+        assert!(contains("0.0.0.0/0", "203.0.113.9"));
+        assert!(contains("fdaa::/16", "fdaa:0:1::3"));
+        assert!(!contains("fdaa::/16", "fdab::1"));
+    }
+
+    #[test]
+    fn ipv4_mapped_addresses_match_ipv4_ranges() {
+        assert!(contains("10.0.0.0/8", "::ffff:10.1.2.3"));
+        assert!(!contains("fdaa::/16", "10.1.2.3"));
+    }
+}
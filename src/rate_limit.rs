@@ -0,0 +1,255 @@
+use crate::app::App;
+use crate::config::RateLimit;
+use crate::error_template::AppError;
+use crate::proxy::ClientInfo;
+use crate::state::AppState;
+use axum::{
+    extract::{MatchedPath, Request, State},
+    http::{header::RETRY_AFTER, HeaderValue, Method, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use leptos::provide_context;
+use std::{
+    collections::HashMap,
+    net::{IpAddr, Ipv6Addr},
+    path::Path,
+    sync::{Mutex, OnceLock, PoisonError},
+    time::{Duration, Instant},
+};
+
+// Probes and scrapes come from a handful of addresses and must never be turned away.
+const EXEMPT_ROUTES: [&str; 4] = ["/healthz", "/readyz", "/version", "/metrics"];
+
+// Clients tracked per route group. When a new client would go past it, buckets idle long
+// enough to have refilled are dropped, then the least recently seen half if that's not enough.
+const MAX_TRACKED_CLIENTS: usize = 10_000;
+
+// Route logged for requests no route matched: 404 pages rendered by the fallback handler.
+const FALLBACK_ROUTE: &str = "fallback";
+
+static LIMITER: OnceLock<RateLimiter> = OnceLock::new();
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum RouteGroup {
+    Api,
+    Pages,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Bucket {
+    tokens: f64,
+    // When the client was last seen.
+    updated: Instant,
+}
+
+impl Bucket {
+    fn refill(&mut self, limit: RateLimit, now: Instant) {
+        let elapsed = now.duration_since(self.updated).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * limit.per_second).min(limit.burst as f64);
+        self.updated = now;
+    }
+}
+
+#[derive(Debug, Default)]
+struct RateLimiter {
+    api: Mutex<HashMap<IpAddr, Bucket>>,
+    pages: Mutex<HashMap<IpAddr, Bucket>>,
+}
+
+impl RateLimiter {
+    // Takes a token from the client's bucket, or returns how many seconds until one is
+    // available again.
+    fn check(&self, group: RouteGroup, ip: IpAddr, limit: RateLimit) -> Result<(), u64> {
+        let now = Instant::now();
+        let buckets = match group {
+            RouteGroup::Api => &self.api,
+            RouteGroup::Pages => &self.pages,
+        };
+        let ip = client_key(ip);
+        let mut buckets = buckets.lock().unwrap_or_else(PoisonError::into_inner);
+        if buckets.len() >= MAX_TRACKED_CLIENTS && !buckets.contains_key(&ip) {
+            evict(&mut buckets, limit, now);
+        }
+
+        let bucket = buckets.entry(ip).or_insert(Bucket {
+            tokens: limit.burst as f64,
+            updated: now,
+        });
+        bucket.refill(limit, now);
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            Ok(())
+        } else if limit.per_second > 0.0 {
+            Err(((1.0 - bucket.tokens) / limit.per_second).ceil() as u64)
+        } else {
+            Err(60)
+        }
+    }
+}
+
+// IPv6 clients are usually handed a whole /64, so they share one bucket per network rather
+// than getting a fresh one for every address they pick.
+fn client_key(ip: IpAddr) -> IpAddr {
+    match ip {
+        IpAddr::V6(ip) => match ip.to_ipv4_mapped() {
+            Some(ip) => IpAddr::V4(ip),
+            None => IpAddr::V6(Ipv6Addr::from(u128::from(ip) & !u128::from(u64::MAX))),
+        },
+        ip => ip,
+    }
+}
+
+// Makes room for at least half of `MAX_TRACKED_CLIENTS`, so it runs once per that many new
+// clients rather than on every request.
+fn evict(buckets: &mut HashMap<IpAddr, Bucket>, limit: RateLimit, now: Instant) {
+    // A client unseen for this long has a full bucket again, the same as a new one.
+    if limit.per_second > 0.0 {
+        let refilled = Duration::from_secs_f64(limit.burst as f64 / limit.per_second);
+        buckets.retain(|_, bucket| now.duration_since(bucket.updated) < refilled);
+    }
+    let keep = MAX_TRACKED_CLIENTS / 2;
+    if buckets.len() > keep {
+        let mut seen: Vec<Instant> = buckets.values().map(|bucket| bucket.updated).collect();
+        let (_, &mut cutoff, _) = seen.select_nth_unstable(buckets.len() - keep);
+        buckets.retain(|_, bucket| bucket.updated > cutoff);
+    }
+}
+
+// Limits every client to the configured rate per route group. Server functions get a plain
+// 429, pages the site's error page.
+pub async fn rate_limit(State(state): State<AppState>, req: Request, next: Next) -> Response {
+    let config = &state.site_config.rate_limit;
+    let ip = req.extensions().get::<ClientInfo>().and_then(|client| client.ip);
+    let Some(ip) = ip.filter(|_| config.enabled) else {
+        return next.run(req).await;
+    };
+    let route = match req.extensions().get::<MatchedPath>() {
+        Some(matched) => matched.as_str().to_string(),
+        // Static files are cheap to serve and every page pulls in a dozen of them, so only the
+        // 404 pages the fallback renders count against the pages group.
+        None if is_static_file(&state.leptos_options.site_root, req.uri().path()).await => {
+            return next.run(req).await;
+        }
+        None => FALLBACK_ROUTE.to_string(),
+    };
+    if EXEMPT_ROUTES.contains(&route.as_str()) {
+        return next.run(req).await;
+    }
+
+    let (group, limit) = if route.starts_with("/api/") {
+        (RouteGroup::Api, config.api)
+    } else {
+        (RouteGroup::Pages, config.pages)
+    };
+    let Err(retry_after) = LIMITER.get_or_init(RateLimiter::default).check(group, ip, limit) else {
+        return next.run(req).await;
+    };
+
+    tracing::warn!(client_ip = %ip, route = %route, "rate limited");
+    // Only page views get the html error page.
+    let mut response = if group == RouteGroup::Api || req.method() != Method::GET {
+        (StatusCode::TOO_MANY_REQUESTS, AppError::RateLimited.to_string()).into_response()
+    } else {
+        let site_config = state.site_config.clone();
+        let handler = leptos_axum::render_app_to_stream_with_context(
+            state.leptos_options.clone(),
+            move || {
+                provide_context(site_config.clone());
+                provide_context(AppError::RateLimited);
+            },
+            App,
+        );
+        handler(req).await.into_response()
+    };
+    if let Ok(value) = HeaderValue::from_str(&retry_after.max(1).to_string()) {
+        response.headers_mut().insert(RETRY_AFTER, value);
+    }
+    response
+}
+
+// Whether the fallback will answer `path` with a file from the site root.
+async fn is_static_file(site_root: &str, path: &str) -> bool {
+    let relative = Path::new(path.trim_start_matches('/'));
+    if relative.components().any(|component| !matches!(component, std::path::Component::Normal(_))) {
+        return false;
+    }
+    tokio::fs::metadata(Path::new(site_root).join(relative))
+        .await
+        .is_ok_and(|metadata| metadata.is_file())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LIMIT: RateLimit = RateLimit { burst: 3, per_second: 1.0 };
+
+    fn ip(n: u32) -> IpAddr {
+        IpAddr::from(n.to_be_bytes())
+    }
+
+    #[test]
+    fn bucket_allows_a_burst_then_refills() {
+        let limiter = RateLimiter::default();
+        for _ in 0..LIMIT.burst {
+            assert_eq!(limiter.check(RouteGroup::Pages, ip(1), LIMIT), Ok(()));
+        }
+        assert_eq!(limiter.check(RouteGroup::Pages, ip(1), LIMIT), Err(1));
+
+        let mut bucket = Bucket { tokens: 0.0, updated: Instant::now() };
+        bucket.refill(LIMIT, bucket.updated + Duration::from_secs(10));
+        assert_eq!(bucket.tokens, LIMIT.burst as f64);
+    }
+
+    #[test]
+    fn clients_and_groups_have_their_own_buckets() {
+        let limiter = RateLimiter::default();
+        for _ in 0..LIMIT.burst {
+            limiter.check(RouteGroup::Api, ip(1), LIMIT).unwrap();
+        }
+        assert!(limiter.check(RouteGroup::Api, ip(1), LIMIT).is_err());
+        assert!(limiter.check(RouteGroup::Api, ip(2), LIMIT).is_ok());
+        assert!(limiter.check(RouteGroup::Pages, ip(1), LIMIT).is_ok());
+    }
+
+    #[test]
+    fn ipv6_clients_share_a_bucket_per_64() {
+        let limiter = RateLimiter::default();
+        let address = |text: &str| text.parse::<IpAddr>().unwrap();
+        for host in 1..=LIMIT.burst {
+            limiter.check(RouteGroup::Pages, address(&format!("2001:db8::{}", host)), LIMIT).unwrap();
+        }
+        assert!(limiter.check(RouteGroup::Pages, address("2001:db8::ffff:1234"), LIMIT).is_err());
+        assert!(limiter.check(RouteGroup::Pages, address("2001:db8:0:1::1"), LIMIT).is_ok());
+        assert_eq!(client_key(address("::ffff:192.0.2.1")), address("192.0.2.1"));
+    }
+
+    #[tokio::test]
+    async fn only_files_under_the_site_root_are_static() {
+        let root = env!("CARGO_MANIFEST_DIR");
+        assert!(is_static_file(root, "/Cargo.toml").await);
+        assert!(!is_static_file(root, "/src").await);
+        assert!(!is_static_file(root, "/blog/missing-post").await);
+        assert!(!is_static_file(&format!("{}/src", root), "/../Cargo.toml").await);
+    }
+
+    #[test]
+    fn eviction_drops_refilled_then_least_recently_seen() {
+        let now = Instant::now();
+        let later = now + Duration::from_secs(60);
+        let mut buckets: HashMap<IpAddr, Bucket> = (0..MAX_TRACKED_CLIENTS as u32)
+            .map(|n| (ip(n), Bucket { tokens: 0.0, updated: now + Duration::from_millis(n as u64) }))
+            .collect();
+        evict(&mut buckets, LIMIT, later);
+        assert!(buckets.is_empty());
+
+        let mut buckets: HashMap<IpAddr, Bucket> = (0..MAX_TRACKED_CLIENTS as u32)
+            .map(|n| (ip(n), Bucket { tokens: 0.0, updated: now + Duration::from_micros(n as u64) }))
+            .collect();
+        evict(&mut buckets, LIMIT, now + Duration::from_secs(1));
+        assert!(buckets.len() <= MAX_TRACKED_CLIENTS / 2);
+        assert!(buckets.contains_key(&ip(MAX_TRACKED_CLIENTS as u32 - 1)));
+        assert!(!buckets.contains_key(&ip(0)));
+    }
+}
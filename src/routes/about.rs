@@ -1,29 +1,32 @@
 use leptos::*;
 use leptos_meta::*;
 
+use crate::config::use_site_config;
+
 use crate::components::footer::{GoBack, HomeFooter};
 
 #[component]
 pub fn About() -> impl IntoView {
-    let title = "About | Cybersecurity & CTF Writeups by Edoardo D'Errico";
-    let description = "Cybersecurity writeups and CTF solutions by Edoardo D'Errico. Technical posts on cryptography, exploit development, and security research.";
-    let url = "https://edoardoderrico.com/about";
+    let site_config = use_site_config();
+    let title = format!("About | {} by {}", site_config.tagline, site_config.author.name);
+    let description = site_config.description.clone();
+    let url = site_config.absolute_url("/about");
 
     view! {
-        <Title text=title />
-        <Meta name="description" content=description />
+        <Title text=title.clone() />
+        <Meta name="description" content=description.clone() />
 
         // Open Graph / Facebook
-        <Meta property="og:url" content=url />
+        <Meta property="og:url" content=url.clone() />
         <Meta property="og:type" content="website" />
-        <Meta property="og:title" content=title />
-        <Meta property="og:description" content=description />
+        <Meta property="og:title" content=title.clone() />
+        <Meta property="og:description" content=description.clone() />
         // <Meta property="og:image" content={image}/>
 
         // Twitter
         <Meta name="twitter:card" content="summary_large_image" />
-        <Meta property="twitter:domain" content="edoardoderrico.com" />
-        <Meta property="twitter:url" content=url />
+        <Meta property="twitter:domain" content=site_config.domain().to_string() />
+        <Meta property="twitter:url" content=url.clone() />
         <Meta name="twitter:title" content=title />
         <Meta name="twitter:description" content=description />
         // <Meta name="twitter:image" content={image}/>
@@ -86,7 +89,11 @@ pub fn About() -> impl IntoView {
 
                         <p>
                             "Note: I am very open to know like-minded people, if you think you are, feel free to "
-                            <a href="mailto:edoardoderrico@outlook.com">"contact me"</a>" !"
+                            {match site_config.social.email.clone() {
+                                Some(email) => view! { <a href=format!("mailto:{}", email)>"contact me"</a> }.into_view(),
+                                None => "contact me".into_view(),
+                            }}
+                            " !"
                         </p>
                     </blockquote>
                 </div>
@@ -0,0 +1,169 @@
+use crate::components::footer::{GoBack, HomeFooter};
+use crate::error_template::error_page;
+use crate::server_functions::stats::{get_site_stats, SiteStats, STATS_DAYS};
+use leptos::*;
+use leptos_meta::*;
+
+#[component]
+pub fn AdminStats() -> impl IntoView {
+    let stats = create_blocking_resource(|| (), |_| async move { get_site_stats().await });
+    view! {
+        <Body class="bg-[#0D1117]" />
+        <Title text="Stats | itehax" />
+        <Meta name="robots" content="noindex, nofollow" />
+        <div class="max-w-5xl mx-auto px-4 py-16 sm:px-6 lg:px-8">
+            <div class="text-center mb-10">
+                <h1 class="text-3xl font-bold text-[#E6EDF3] mb-2">"Stats"</h1>
+                <p class="text-[#8B949E]">{format!("Page views of the last {} days.", STATS_DAYS)}</p>
+            </div>
+            <Suspense fallback=|| view! { <p class="text-[#8B949E] text-center">"Loading..."</p> }>
+                {move || {
+                    stats
+                        .get()
+                        .map(|stats| match stats {
+                            Ok(stats) => view! { <StatsView stats /> }.into_view(),
+                            Err(e) => error_page(e.into()),
+                        })
+                }}
+            </Suspense>
+        </div>
+        <GoBack content="Back to Home".to_string() url="".to_string() />
+        <HomeFooter />
+    }
+}
+
+#[component]
+fn StatsView(stats: SiteStats) -> impl IntoView {
+    let total_views: u64 = stats.views.iter().sum();
+    let today_visitors = stats.visitors.last().copied().unwrap_or_default();
+    // Today is still in progress, so the estimate comes from the last complete day.
+    let subscribers = stats.subscribers.iter().rev().nth(1).copied().unwrap_or_default();
+    let first_day = stats.days.first().cloned().unwrap_or_default();
+    let last_day = stats.days.last().cloned().unwrap_or_default();
+
+    let daily_views = view! { <Bars values=stats.views days=stats.days.clone() /> };
+    let daily_subscribers = view! { <Bars values=stats.subscribers days=stats.days.clone() /> };
+    let posts = if stats.posts.is_empty() {
+        view! { <p class="text-[#8B949E]">"No views yet."</p> }.into_view()
+    } else {
+        let days = stats.days;
+        view! {
+            <table class="itx-stats-table">
+                <tbody>
+                    {stats
+                        .posts
+                        .into_iter()
+                        .map(|post| {
+                            view! {
+                                <tr>
+                                    <td>
+                                        <a class="text-[#58A6FF]" href=post.href>
+                                            {post.title}
+                                        </a>
+                                    </td>
+                                    <td class="itx-stats-spark">
+                                        <Bars values=post.views days=days.clone() />
+                                    </td>
+                                    <td class="itx-stats-count text-[#E6EDF3]">{post.total}</td>
+                                </tr>
+                            }
+                        })
+                        .collect_view()}
+                </tbody>
+            </table>
+        }
+            .into_view()
+    };
+    let referrers = view! { <Counts counts=stats.referrers /> };
+    let countries = view! { <Counts counts=stats.countries /> };
+
+    view! {
+        <div class="itx-stats-summary">
+            <Summary label="Views" value=total_views />
+            <Summary label="Visitors today" value=today_visitors />
+            <Summary label="Feed readers" value=subscribers />
+        </div>
+
+        <Section title="Views per day">
+            {daily_views}
+            <div class="itx-stats-axis text-[#8B949E]">
+                <span>{first_day}</span>
+                <span>{last_day}</span>
+            </div>
+        </Section>
+        <Section title="Posts">{posts}</Section>
+        <Section title="Feed readers per day">{daily_subscribers}</Section>
+        <div class="itx-stats-columns">
+            <Section title="Top referrers">{referrers}</Section>
+            <Section title="Countries">{countries}</Section>
+        </div>
+    }
+}
+
+#[component]
+fn Summary(label: &'static str, value: u64) -> impl IntoView {
+    view! {
+        <div class="border border-[#30363D] rounded-xl p-4 bg-[#161B22] text-center">
+            <div class="text-3xl font-bold text-[#E6EDF3]">{value}</div>
+            <div class="text-[#8B949E]">{label}</div>
+        </div>
+    }
+}
+
+#[component]
+fn Section(title: &'static str, children: Children) -> impl IntoView {
+    view! {
+        <section class="itx-stats-section border border-[#30363D] rounded-xl p-4 bg-[#161B22]">
+            <h2 class="text-xl font-bold text-[#E6EDF3] mb-4">{title}</h2>
+            {children()}
+        </section>
+    }
+}
+
+// One bar per day, scaled to the busiest day.
+#[component]
+fn Bars(values: Vec<u64>, days: Vec<String>) -> impl IntoView {
+    let max = values.iter().copied().max().unwrap_or_default().max(1);
+    view! {
+        <div class="itx-stats-bars">
+            {values
+                .into_iter()
+                .zip(days)
+                .map(|(value, day)| {
+                    view! {
+                        <span
+                            class="bg-[#58A6FF]"
+                            style=format!("height: {}%", value * 100 / max)
+                            title=format!("{}: {}", day, value)
+                        ></span>
+                    }
+                })
+                .collect_view()}
+        </div>
+    }
+}
+
+#[component]
+fn Counts(counts: Vec<(String, u64)>) -> impl IntoView {
+    if counts.is_empty() {
+        return view! { <p class="text-[#8B949E]">"Nothing yet."</p> }.into_view();
+    }
+    view! {
+        <table class="itx-stats-table">
+            <tbody>
+                {counts
+                    .into_iter()
+                    .map(|(name, count)| {
+                        view! {
+                            <tr>
+                                <td class="text-[#8B949E]">{name}</td>
+                                <td class="itx-stats-count text-[#E6EDF3]">{count}</td>
+                            </tr>
+                        }
+                    })
+                    .collect_view()}
+            </tbody>
+        </table>
+    }
+    .into_view()
+}
@@ -1,2 +0,0 @@
-pub mod blog_article;
-pub mod blog_section;
@@ -0,0 +1,56 @@
+use crate::components::post::{Post, RenderPost};
+use crate::config::{use_site_config, CollectionConfig};
+use leptos::{nonce::use_nonce, *};
+use leptos_meta::*;
+
+#[component]
+pub fn CollectionSection(collection: CollectionConfig) -> impl IntoView {
+    let site_config = use_site_config();
+    let url = site_config.absolute_url(&collection.path());
+    let title = collection.title.clone();
+    let description = collection.description.clone();
+    view! {
+        <Title text=title.clone() />
+        <Meta name="description" content=description.clone() />
+        {collection
+            .keywords
+            .clone()
+            .map(|keywords| view! { <Meta name="keywords" content=keywords /> })}
+
+        // Open Graph / Facebook
+        <Meta property="og:url" content=url.clone() />
+        <Meta property="og:type" content="website" />
+        <Meta property="og:title" content=title.clone() />
+        <Meta property="og:description" content=description.clone() />
+        // <Meta property="og:image" content={image}/>
+
+        // Twitter
+        <Meta name="twitter:card" content="summary_large_image" />
+        <Meta property="twitter:domain" content=site_config.domain().to_string() />
+        <Meta property="twitter:url" content=url.clone() />
+        <Meta name="twitter:title" content=title />
+        <Meta name="twitter:description" content=description />
+        // <Meta name="twitter:image" content={image}/>
+
+        // Canonical URL
+        <Link rel="canonical" href=url />
+        <Post collection />
+    }
+}
+
+#[component]
+pub fn CollectionArticle(collection: CollectionConfig) -> impl IntoView {
+    view! {
+        <Link rel="stylesheet" href="/highlighter/styles/github.min.css" />
+        <Link
+            rel="stylesheet"
+            href=r#"https://cdn.jsdelivr.net/npm/katex@0.16.11/dist/katex.min.css"#
+            integrity="sha384-nB0miv6/jRmo5UMMR1wu3Gz6NLsoTkbqJghGIsx//Rlm+ZU03BU6SQNC66uf4l5+"
+            crossorigin="anonymous"
+        />
+        <Link rel="stylesheet" href="/highlighter/styles/katex.css" />
+
+        <script nonce=use_nonce() defer src="/highlighter/load_highlight.js"></script>
+        <RenderPost collection />
+    }
+}
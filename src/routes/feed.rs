@@ -1,5 +1,7 @@
 cfg_if::cfg_if! {
     if #[cfg(feature="ssr")] {
+        use crate::config::site_config;
+        use crate::escape::escape_xml;
         use crate::server_functions::posts::get_posts;
         use axum::{
             http::{header::CONTENT_TYPE, HeaderMap},
@@ -14,20 +16,25 @@ cfg_if::cfg_if! {
         }
 
         async fn build_rss_xml() -> String {
+            let config = site_config();
             let mut xml = String::new();
-            xml.push_str(
+            xml.push_str(&format!(
                 r#"<?xml version="1.0" encoding="utf-8"?>
                 <rss version="2.0" xmlns:atom="http://www.w3.org/2005/Atom">
-                <channel><title>Itehax</title>
-                <link>https://itehax.com</link>
-                <description>Itehax website, coding, hacking, reading and much more.</description>
+                <channel><title>{}</title>
+                <link>{}</link>
+                <description>{}</description>
                 <language>en-us</language>
-                <atom:link href="https://itehax.com/rss.xml" rel="self" type="application/rss+xml"/>"#,
-            );
+                <atom:link href="{}" rel="self" type="application/rss+xml"/>"#,
+                escape_xml(&config.name),
+                escape_xml(&config.absolute_url("/")),
+                escape_xml(&config.description),
+                escape_xml(&config.absolute_url("/feed.xml")),
+            ));
 
             let posts = get_posts().await.unwrap();
-            for (post_type, posts_vec) in posts {
-                for post in posts_vec {
+            for collection in config.collections.iter().filter(|collection| collection.in_feed) {
+                for post in posts.get(&collection.name).into_iter().flatten() {
                     //todo add real time to rfc2822 conversion.
                     let parsed_date = NaiveDate::parse_from_str(&post.post_metadata.date, "%Y-%m-%d")
                         .expect("Failed to parse date string")
@@ -37,13 +44,12 @@ cfg_if::cfg_if! {
                     let post_name = post.post_metadata.create_href();
 
                     let channel = format!(
-                        r#"<item><title>{}</title><link>https://itehax.com/{}/{}</link><description>{}</description><language>en-us</language><pubDate>{}</pubDate><guid isPermaLink="false">{}</guid></item>"#,
-                        post.post_metadata.title,
-                        post_type.to_string(),
-                        post_name,
-                        post.post_metadata.description,
+                        r#"<item><title>{}</title><link>{}</link><description>{}</description><language>en-us</language><pubDate>{}</pubDate><guid isPermaLink="false">{}</guid></item>"#,
+                        escape_xml(&post.post_metadata.title),
+                        escape_xml(&config.absolute_url(&collection.href(&post_name))),
+                        escape_xml(&post.post_metadata.description),
                         parsed_date,
-                        post_name,
+                        escape_xml(&post_name),
                     );
 
                     xml.push_str(&channel);
@@ -0,0 +1,150 @@
+cfg_if::cfg_if! {
+    if #[cfg(feature="ssr")] {
+        use crate::config::site_config;
+        use crate::content::content;
+        use crate::escape::escape_xml;
+        use crate::server_functions::graph::GraphData;
+        use axum::{
+            http::{header::CONTENT_TYPE, HeaderMap, StatusCode},
+            response::{IntoResponse, Response},
+        };
+        use serde_json::json;
+
+        pub async fn graph_dot() -> Response {
+            export("text/vnd.graphviz; charset=utf-8", build_dot).await
+        }
+
+        pub async fn graph_graphml() -> Response {
+            export("application/graphml+xml; charset=utf-8", build_graphml).await
+        }
+
+        pub async fn graph_json() -> Response {
+            export("application/ld+json", build_json_ld).await
+        }
+
+        // The graph of the current posts, rebuilt on the blocking pool when they changed.
+        async fn export(content_type: &'static str, build: fn(&GraphData) -> String) -> Response {
+            match tokio::task::spawn_blocking(content).await {
+                Ok(content) => (build_headers(content_type), build(&content.graph)).into_response(),
+                Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, format!("Threading error: {}", e)).into_response(),
+            }
+        }
+
+        fn build_dot(graph: &GraphData) -> String {
+            let mut dot = String::from("graph posts {\n");
+            for node in &graph.nodes {
+                dot.push_str(&format!(
+                    "  \"{}\" [label=\"{}\", type=\"{}\", date=\"{}\", tags=\"{}\"];\n",
+                    escape_dot(&node.id),
+                    escape_dot(&node.title),
+                    escape_dot(&node.collection),
+                    escape_dot(&node.date),
+                    escape_dot(&node.tags.join(",")),
+                ));
+            }
+            for edge in &graph.edges {
+                dot.push_str(&format!(
+                    "  \"{}\" -- \"{}\" [label=\"{}\", weight={}];\n",
+                    escape_dot(&edge.source),
+                    escape_dot(&edge.target),
+                    escape_dot(&edge.label),
+                    edge.weight,
+                ));
+            }
+            dot.push_str("}\n");
+            dot
+        }
+
+        fn build_graphml(graph: &GraphData) -> String {
+            let mut xml = String::from(
+                r#"<?xml version="1.0" encoding="UTF-8"?>
+<graphml xmlns="http://graphml.graphdrawing.org/xmlns" xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance" xsi:schemaLocation="http://graphml.graphdrawing.org/xmlns http://graphml.graphdrawing.org/xmlns/1.0/graphml.xsd">
+  <key id="title" for="node" attr.name="title" attr.type="string"/>
+  <key id="type" for="node" attr.name="type" attr.type="string"/>
+  <key id="date" for="node" attr.name="date" attr.type="string"/>
+  <key id="tags" for="node" attr.name="tags" attr.type="string"/>
+  <key id="degree" for="node" attr.name="degree" attr.type="int"/>
+  <key id="label" for="edge" attr.name="label" attr.type="string"/>
+  <key id="weight" for="edge" attr.name="weight" attr.type="double"/>
+  <key id="links" for="edge" attr.name="links" attr.type="int"/>
+  <graph id="posts" edgedefault="undirected">
+"#,
+            );
+            for node in &graph.nodes {
+                xml.push_str(&format!(
+                    r#"    <node id="{}"><data key="title">{}</data><data key="type">{}</data><data key="date">{}</data><data key="tags">{}</data><data key="degree">{}</data></node>
+"#,
+                    escape_xml(&node.id),
+                    escape_xml(&node.title),
+                    escape_xml(&node.collection),
+                    escape_xml(&node.date),
+                    escape_xml(&node.tags.join(",")),
+                    node.degree,
+                ));
+            }
+            for (i, edge) in graph.edges.iter().enumerate() {
+                xml.push_str(&format!(
+                    r#"    <edge id="e{}" source="{}" target="{}"><data key="label">{}</data><data key="weight">{}</data><data key="links">{}</data></edge>
+"#,
+                    i,
+                    escape_xml(&edge.source),
+                    escape_xml(&edge.target),
+                    escape_xml(&edge.label),
+                    edge.weight,
+                    edge.link_count,
+                ));
+            }
+            xml.push_str("  </graph>\n</graphml>\n");
+            xml
+        }
+
+        // Posts are described with schema.org types; edges use a small site-local vocabulary.
+        fn build_json_ld(graph: &GraphData) -> String {
+            let config = site_config();
+            let nodes = graph.nodes.iter().map(|node| {
+                json!({
+                    "@id": config.absolute_url(&node.href),
+                    "@type": config
+                        .collection(&node.collection)
+                        .map_or("CreativeWork", |collection| collection.schema_type.as_str()),
+                    "name": node.title,
+                    "url": config.absolute_url(&node.href),
+                    "datePublished": node.date,
+                    "keywords": node.tags,
+                    "genre": node.collection,
+                    "itx:degree": node.degree,
+                })
+            });
+            let edges = graph.edges.iter().map(|edge| {
+                json!({
+                    "@type": "itx:Edge",
+                    "itx:source": { "@id": config.absolute_url(&edge.source) },
+                    "itx:target": { "@id": config.absolute_url(&edge.target) },
+                    "itx:label": edge.label,
+                    "itx:sharedTags": edge.shared_tags,
+                    "itx:linkCount": edge.link_count,
+                    "itx:weight": edge.weight,
+                })
+            });
+
+            let document = json!({
+                "@context": {
+                    "@vocab": "https://schema.org/",
+                    "itx": config.absolute_url("/graph#"),
+                },
+                "@graph": nodes.chain(edges).collect::<Vec<_>>(),
+            });
+            serde_json::to_string_pretty(&document).unwrap_or_default()
+        }
+
+        fn escape_dot(text: &str) -> String {
+            text.replace('\\', "\\\\").replace('"', "\\\"")
+        }
+
+        fn build_headers(content_type: &'static str) -> HeaderMap {
+            let mut headers = HeaderMap::new();
+            headers.insert(CONTENT_TYPE, content_type.parse().unwrap());
+            headers
+        }
+    }
+}
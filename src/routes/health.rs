@@ -0,0 +1,96 @@
+cfg_if::cfg_if! {
+    if #[cfg(feature="ssr")] {
+        use crate::build_info::build_info;
+        use crate::config::site_config;
+        use crate::content::content;
+        use crate::server_functions::posts::{get_posts_file, PostIndex};
+        use axum::{
+            http::{header::CACHE_CONTROL, HeaderMap, HeaderValue, StatusCode},
+            response::{IntoResponse, Json},
+        };
+        use serde_json::json;
+        use std::collections::BTreeMap;
+
+        // The process is up and serving requests.
+        pub async fn healthz() -> impl IntoResponse {
+            (build_headers(), "ok")
+        }
+
+        // Every content directory can be read and every post in it has valid frontmatter.
+        // Broken links and includes are listed too, but leave the instance ready: the post
+        // still renders, and one bad reference shouldn't take the site out of rotation.
+        pub async fn readyz() -> impl IntoResponse {
+            let (errors, post_errors) = tokio::task::spawn_blocking(|| (content_errors(), content().errors()))
+                .await
+                .unwrap_or_else(|e| (vec![format!("content check failed: {}", e)], Vec::new()));
+            if errors.is_empty() {
+                (
+                    StatusCode::OK,
+                    build_headers(),
+                    Json(json!({ "status": "ready", "post_errors": post_errors })),
+                )
+            } else {
+                (
+                    StatusCode::SERVICE_UNAVAILABLE,
+                    build_headers(),
+                    Json(json!({ "status": "not ready", "errors": errors, "post_errors": post_errors })),
+                )
+            }
+        }
+
+        pub async fn version() -> impl IntoResponse {
+            let info = build_info();
+            // Counted from the index, which only reads the posts again when they changed.
+            let posts = tokio::task::spawn_blocking(|| {
+                let mut posts: BTreeMap<String, usize> = site_config()
+                    .collections
+                    .iter()
+                    .map(|collection| (collection.name.clone(), 0))
+                    .collect();
+                for entry in &content().index.entries {
+                    let collection = entry.file.split('/').next().unwrap_or_default();
+                    if let Some(count) = posts.get_mut(collection) {
+                        *count += 1;
+                    }
+                }
+                posts
+            })
+            .await
+            .unwrap_or_default();
+            (
+                build_headers(),
+                Json(json!({
+                    "commit": info.commit,
+                    "build_time": info.build_time,
+                    "last_updated": info.last_updated,
+                    "posts": posts,
+                })),
+            )
+        }
+
+        fn content_errors() -> Vec<String> {
+            let mut errors = Vec::new();
+            let mut files = 0;
+            for collection in &site_config().collections {
+                match std::fs::read_dir(&collection.dir) {
+                    Ok(_) => files += get_posts_file(&collection.dir).len(),
+                    Err(e) => errors.push(format!("unable to read {}: {}", collection.dir, e)),
+                }
+            }
+            // The index skips posts whose frontmatter doesn't parse. A fresh one, read at the
+            // same time as the files were counted.
+            let index = PostIndex::build();
+            let indexed = index.entries.len() + index.tombstones.len();
+            if errors.is_empty() && indexed < files {
+                errors.push(format!("{} of {} posts could not be indexed", files - indexed, files));
+            }
+            errors
+        }
+
+        fn build_headers() -> HeaderMap {
+            let mut headers = HeaderMap::new();
+            headers.insert(CACHE_CONTROL, HeaderValue::from_static("no-store"));
+            headers
+        }
+    }
+}
@@ -1,8 +1,9 @@
 pub mod about;
-pub mod blog;
+pub mod admin;
+pub mod collection;
 pub mod feed;
 pub mod graph;
+pub mod graph_export;
+pub mod health;
 pub mod home;
-//pub mod projects;
-pub mod writing;
 pub mod manifesto;
\ No newline at end of file
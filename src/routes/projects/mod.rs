@@ -1,2 +0,0 @@
-pub mod projects_article;
-pub mod projects_section;
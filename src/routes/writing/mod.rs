@@ -1,2 +0,0 @@
-pub mod writing_article;
-pub mod writing_section;
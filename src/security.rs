@@ -0,0 +1,131 @@
+use crate::config::is_dev_mode;
+use crate::state::AppState;
+use axum::{
+    body::Bytes,
+    extract::{Request, State},
+    http::{
+        header::{
+            CONTENT_SECURITY_POLICY, CONTENT_SECURITY_POLICY_REPORT_ONLY, REFERRER_POLICY,
+            STRICT_TRANSPORT_SECURITY, X_CONTENT_TYPE_OPTIONS,
+        },
+        HeaderName, HeaderValue, StatusCode,
+    },
+    middleware::Next,
+    response::Response,
+};
+use leptos::{nonce::use_nonce, use_context};
+use leptos_axum::ResponseOptions;
+
+// Internal header carrying the nonce of a rendered page from the app to `security_headers`.
+// It never leaves the server.
+pub const NONCE_HEADER: &str = "x-csp-nonce";
+
+pub const CSP_REPORT_PATH: &str = "/csp-report";
+
+const PERMISSIONS_POLICY: &str =
+    "camera=(), microphone=(), geolocation=(), payment=(), usb=(), interest-cohort=()";
+
+// Stylesheets and fonts loaded from outside the site: Google Fonts and KaTeX.
+const STYLE_ORIGINS: &str = "https://fonts.googleapis.com https://cdn.jsdelivr.net";
+const FONT_ORIGINS: &str = "https://fonts.gstatic.com https://cdn.jsdelivr.net";
+
+// Called from `App` while rendering on the server, so the middleware can build the policy for
+// the nonce leptos put on its scripts.
+pub fn expose_nonce() {
+    let (Some(nonce), Some(response)) = (use_nonce(), use_context::<ResponseOptions>()) else {
+        return;
+    };
+    if let Ok(value) = HeaderValue::from_str(&nonce.to_string()) {
+        response.insert_header(HeaderName::from_static(NONCE_HEADER), value);
+    }
+}
+
+// Scripts only run with the page nonce; 'strict-dynamic' lets them load the wasm bundle, d3
+// and the other scripts they insert. Inline styles are still allowed, since the markup uses
+// `style` attributes.
+fn content_security_policy(nonce: &str, reload_port: u32) -> String {
+    let connect_src = if is_dev_mode() {
+        format!("'self' ws://localhost:{reload_port} ws://127.0.0.1:{reload_port}")
+    } else {
+        "'self'".to_string()
+    };
+    [
+        "default-src 'self'".to_string(),
+        format!("script-src 'nonce-{nonce}' 'strict-dynamic' 'wasm-unsafe-eval'"),
+        format!("style-src 'self' 'unsafe-inline' {STYLE_ORIGINS}"),
+        format!("font-src 'self' {FONT_ORIGINS}"),
+        "img-src 'self' data: https:".to_string(),
+        format!("connect-src {connect_src}"),
+        "frame-src 'self'".to_string(),
+        "object-src 'none'".to_string(),
+        "base-uri 'self'".to_string(),
+        "form-action 'self'".to_string(),
+        "frame-ancestors 'none'".to_string(),
+        format!("report-uri {CSP_REPORT_PATH}"),
+    ]
+    .join("; ")
+}
+
+// Adds the security headers to every response, and the CSP to pages rendered with a nonce.
+pub async fn security_headers(State(state): State<AppState>, req: Request, next: Next) -> Response {
+    let mut response = next.run(req).await;
+    let security = &state.site_config.security;
+    let headers = response.headers_mut();
+
+    if let Ok(value) = HeaderValue::from_str(&format!("max-age={}; includeSubDomains", security.hsts_max_age)) {
+        headers.insert(STRICT_TRANSPORT_SECURITY, value);
+    }
+    headers.insert(X_CONTENT_TYPE_OPTIONS, HeaderValue::from_static("nosniff"));
+    headers.insert(REFERRER_POLICY, HeaderValue::from_static("strict-origin-when-cross-origin"));
+    headers.insert(HeaderName::from_static("permissions-policy"), HeaderValue::from_static(PERMISSIONS_POLICY));
+
+    // A 304 carries no nonce: the browser keeps the policy it stored with the cached page,
+    // which matches the nonce in that page.
+    let Some(nonce) = headers.remove(NONCE_HEADER) else {
+        return response;
+    };
+    let Ok(nonce) = nonce.to_str() else {
+        return response;
+    };
+    let policy = content_security_policy(nonce, state.leptos_options.reload_port);
+    let header = if security.csp_report_only {
+        CONTENT_SECURITY_POLICY_REPORT_ONLY
+    } else {
+        CONTENT_SECURITY_POLICY
+    };
+    if let Ok(value) = HeaderValue::from_str(&policy) {
+        headers.insert(header, value);
+    }
+    response
+}
+
+// Receives `report-uri` violation reports, sent as `application/csp-report` or, by browsers
+// using the Reporting API, as `application/reports+json`.
+pub async fn csp_report(body: Bytes) -> StatusCode {
+    let Ok(report) = serde_json::from_slice::<serde_json::Value>(&body) else {
+        return StatusCode::BAD_REQUEST;
+    };
+    let reports = match report {
+        serde_json::Value::Array(reports) => reports
+            .into_iter()
+            .filter_map(|report| report.get("body").cloned())
+            .collect(),
+        report => vec![report.get("csp-report").cloned().unwrap_or(report)],
+    };
+    for report in reports {
+        let field = |names: &[&str]| {
+            names
+                .iter()
+                .find_map(|name| report.get(*name).and_then(|value| value.as_str()))
+                .unwrap_or("-")
+                .to_string()
+        };
+        leptos::logging::warn!(
+            "csp violation on {}: {} blocked {}",
+            field(&["document-uri", "documentURL"]),
+            field(&["effective-directive", "violated-directive", "effectiveDirective"]),
+            field(&["blocked-uri", "blockedURL"]),
+        );
+    }
+    StatusCode::NO_CONTENT
+}
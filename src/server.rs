@@ -0,0 +1,308 @@
+use crate::config::ServerConfig;
+use axum::{extract::Request, Router};
+use hyper_util::{
+    rt::{TokioExecutor, TokioIo},
+    server::{conn::auto, graceful::GracefulShutdown},
+    service::TowerToHyperService,
+};
+use std::{
+    io::{self, BufReader},
+    net::SocketAddr,
+    path::PathBuf,
+    pin::pin,
+    sync::{Arc, RwLock},
+    time::{Duration, SystemTime},
+};
+use tokio::io::{AsyncRead, AsyncWrite};
+use tokio::net::TcpListener;
+use tokio_rustls::{
+    rustls::{
+        crypto::ring,
+        server::{ClientHello, ResolvesServerCert},
+        sign::CertifiedKey,
+        ServerConfig as TlsConfig,
+    },
+    TlsAcceptor,
+};
+use tower::ServiceExt;
+
+// How often the certificate files are checked for changes.
+const CERT_RELOAD_INTERVAL: Duration = Duration::from_secs(60);
+
+// Clients that connect and never finish the handshake are dropped after this long.
+const TLS_HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(10);
+
+// Pause after a failed accept, e.g. when out of file descriptors, so the loop doesn't spin
+// while connections close.
+const ACCEPT_ERROR_BACKOFF: Duration = Duration::from_millis(100);
+
+// The connection a request came in on, added to the request extensions.
+#[derive(Debug, Clone, Copy)]
+pub struct ConnectionInfo {
+    // `None` for connections over the Unix socket.
+    pub remote: Option<SocketAddr>,
+    pub tls: bool,
+}
+
+trait Io: AsyncRead + AsyncWrite + Unpin + Send {}
+impl<T: AsyncRead + AsyncWrite + Unpin + Send> Io for T {}
+
+enum Listener {
+    Tcp(TcpListener),
+    #[cfg(unix)]
+    Unix(tokio::net::UnixListener, PathBuf),
+}
+
+impl Listener {
+    async fn bind(addr: SocketAddr, config: &ServerConfig) -> io::Result<Self> {
+        let Some(path) = &config.unix_socket else {
+            return Ok(Listener::Tcp(TcpListener::bind(addr).await?));
+        };
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::FileTypeExt;
+
+            // A socket left behind by a previous run would make the bind fail. Anything else
+            // at that path is left alone.
+            match std::fs::symlink_metadata(path) {
+                Ok(metadata) if metadata.file_type().is_socket() => std::fs::remove_file(path)?,
+                Ok(_) => {
+                    return Err(io::Error::new(
+                        io::ErrorKind::AlreadyExists,
+                        format!("{} exists and is not a socket", path),
+                    ))
+                }
+                Err(e) if e.kind() != io::ErrorKind::NotFound => return Err(e),
+                Err(_) => {}
+            }
+            let listener = tokio::net::UnixListener::bind(path)?;
+            Ok(Listener::Unix(listener, PathBuf::from(path)))
+        }
+        #[cfg(not(unix))]
+        {
+            Err(io::Error::new(io::ErrorKind::Unsupported, format!("unix sockets are not supported: {}", path)))
+        }
+    }
+
+    async fn accept(&self) -> io::Result<(Box<dyn Io>, Option<SocketAddr>)> {
+        match self {
+            Listener::Tcp(listener) => {
+                let (stream, remote) = listener.accept().await?;
+                Ok((Box::new(stream), Some(remote)))
+            }
+            #[cfg(unix)]
+            Listener::Unix(listener, _) => {
+                let (stream, _) = listener.accept().await?;
+                Ok((Box::new(stream), None))
+            }
+        }
+    }
+
+    fn describe(&self, tls: bool) -> String {
+        match self {
+            Listener::Tcp(listener) => {
+                let scheme = if tls { "https" } else { "http" };
+                match listener.local_addr() {
+                    Ok(addr) => format!("{}://{}", scheme, addr),
+                    Err(_) => scheme.to_string(),
+                }
+            }
+            #[cfg(unix)]
+            Listener::Unix(_, path) => format!("unix:{}", path.display()),
+        }
+    }
+}
+
+impl Drop for Listener {
+    fn drop(&mut self) {
+        #[cfg(unix)]
+        if let Listener::Unix(_, path) = self {
+            let _ = std::fs::remove_file(path);
+        }
+    }
+}
+
+// Serves `app` until SIGTERM or SIGINT, then stops accepting connections and gives the open
+// ones `drain_timeout` seconds to finish.
+pub async fn serve(app: Router, addr: SocketAddr, config: &ServerConfig) -> io::Result<()> {
+    let listener = Listener::bind(addr, config).await?;
+    let tls = match (&config.tls_cert, &config.tls_key) {
+        (Some(cert), Some(key)) => Some(tls_acceptor(PathBuf::from(cert), PathBuf::from(key))?),
+        (None, None) => None,
+        _ => {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "tls_cert and tls_key must be set together",
+            ))
+        }
+    };
+    tracing::info!("listening on {}", listener.describe(tls.is_some()));
+
+    let graceful = GracefulShutdown::new();
+    let mut shutdown = pin!(shutdown_signal());
+    loop {
+        let (stream, remote) = tokio::select! {
+            accepted = listener.accept() => match accepted {
+                Ok(accepted) => accepted,
+                Err(e) => {
+                    tracing::warn!("failed to accept connection: {}", e);
+                    tokio::time::sleep(ACCEPT_ERROR_BACKOFF).await;
+                    continue;
+                }
+            },
+            _ = &mut shutdown => break,
+        };
+
+        let info = ConnectionInfo {
+            remote,
+            tls: tls.is_some(),
+        };
+        let service = TowerToHyperService::new(app.clone().map_request(move |mut req: Request<_>| {
+            req.extensions_mut().insert(info);
+            req
+        }));
+        let tls = tls.clone();
+        let watcher = graceful.watcher();
+        tokio::spawn(async move {
+            let builder = auto::Builder::new(TokioExecutor::new());
+            let stream: Box<dyn Io> = match tls {
+                Some(acceptor) => match tokio::time::timeout(TLS_HANDSHAKE_TIMEOUT, acceptor.accept(stream)).await {
+                    Ok(Ok(stream)) => Box::new(stream),
+                    Ok(Err(e)) => {
+                        tracing::debug!("tls handshake failed: {}", e);
+                        return;
+                    }
+                    Err(_) => {
+                        tracing::debug!("tls handshake timed out");
+                        return;
+                    }
+                },
+                None => stream,
+            };
+            let connection = builder.serve_connection_with_upgrades(TokioIo::new(stream), service);
+            if let Err(e) = watcher.watch(connection).await {
+                tracing::debug!("connection error: {}", e);
+            }
+        });
+    }
+
+    drop(listener);
+    let drain_timeout = Duration::from_secs(config.drain_timeout);
+    tracing::info!(
+        "shutting down, waiting up to {}s for {} open connections",
+        drain_timeout.as_secs(),
+        graceful.count()
+    );
+    if tokio::time::timeout(drain_timeout, graceful.shutdown()).await.is_err() {
+        tracing::warn!("drain timeout elapsed, closing the remaining connections");
+    }
+    Ok(())
+}
+
+async fn shutdown_signal() {
+    let ctrl_c = async {
+        if let Err(e) = tokio::signal::ctrl_c().await {
+            tracing::warn!("unable to listen for ctrl-c: {}", e);
+            std::future::pending::<()>().await;
+        }
+    };
+    #[cfg(unix)]
+    let terminate = async {
+        use tokio::signal::unix::{signal, SignalKind};
+        match signal(SignalKind::terminate()) {
+            Ok(mut terminate) => {
+                terminate.recv().await;
+            }
+            Err(e) => {
+                tracing::warn!("unable to listen for SIGTERM: {}", e);
+                std::future::pending::<()>().await;
+            }
+        }
+    };
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = ctrl_c => {}
+        _ = terminate => {}
+    }
+}
+
+fn tls_acceptor(cert_path: PathBuf, key_path: PathBuf) -> io::Result<TlsAcceptor> {
+    let resolver = Arc::new(ReloadingCert {
+        key: RwLock::new(Arc::new(load_certified_key(&cert_path, &key_path)?)),
+        modified: RwLock::new(modified(&cert_path, &key_path)),
+        cert_path,
+        key_path,
+    });
+    tokio::spawn(resolver.clone().watch());
+
+    let mut config = TlsConfig::builder_with_provider(Arc::new(ring::default_provider()))
+        .with_safe_default_protocol_versions()
+        .map_err(io::Error::other)?
+        .with_no_client_auth()
+        .with_cert_resolver(resolver);
+    config.alpn_protocols = vec![b"h2".to_vec(), b"http/1.1".to_vec()];
+    Ok(TlsAcceptor::from(Arc::new(config)))
+}
+
+// Serves the certificate currently on disk, so renewals are picked up without a restart.
+#[derive(Debug)]
+struct ReloadingCert {
+    cert_path: PathBuf,
+    key_path: PathBuf,
+    key: RwLock<Arc<CertifiedKey>>,
+    modified: RwLock<Option<SystemTime>>,
+}
+
+impl ReloadingCert {
+    async fn watch(self: Arc<Self>) {
+        let mut interval = tokio::time::interval(CERT_RELOAD_INTERVAL);
+        loop {
+            interval.tick().await;
+            let modified = modified(&self.cert_path, &self.key_path);
+            if modified == *self.modified.read().unwrap() {
+                continue;
+            }
+            // A failed load keeps the old certificate and is retried on the next change.
+            match load_certified_key(&self.cert_path, &self.key_path) {
+                Ok(key) => {
+                    *self.key.write().unwrap() = Arc::new(key);
+                    tracing::info!("reloaded tls certificate {}", self.cert_path.display());
+                }
+                Err(e) => tracing::warn!("unable to reload tls certificate: {}", e),
+            }
+            *self.modified.write().unwrap() = modified;
+        }
+    }
+}
+
+impl ResolvesServerCert for ReloadingCert {
+    fn resolve(&self, _client_hello: ClientHello) -> Option<Arc<CertifiedKey>> {
+        Some(self.key.read().unwrap().clone())
+    }
+}
+
+// Latest modification time of the two files.
+fn modified(cert_path: &PathBuf, key_path: &PathBuf) -> Option<SystemTime> {
+    [cert_path, key_path]
+        .iter()
+        .filter_map(|path| std::fs::metadata(path).and_then(|metadata| metadata.modified()).ok())
+        .max()
+}
+
+fn load_certified_key(cert_path: &PathBuf, key_path: &PathBuf) -> io::Result<CertifiedKey> {
+    let invalid = |message: String| io::Error::new(io::ErrorKind::InvalidData, message);
+
+    let mut cert_file = BufReader::new(std::fs::File::open(cert_path)?);
+    let certs = rustls_pemfile::certs(&mut cert_file).collect::<Result<Vec<_>, _>>()?;
+    if certs.is_empty() {
+        return Err(invalid(format!("no certificates in {}", cert_path.display())));
+    }
+    let mut key_file = BufReader::new(std::fs::File::open(key_path)?);
+    let key = rustls_pemfile::private_key(&mut key_file)?
+        .ok_or_else(|| invalid(format!("no private key in {}", key_path.display())))?;
+    let signing_key = ring::sign::any_supported_type(&key)
+        .map_err(|e| invalid(format!("unsupported private key in {}: {}", key_path.display(), e)))?;
+    Ok(CertifiedKey::new(certs, signing_key))
+}
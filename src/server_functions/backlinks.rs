@@ -0,0 +1,143 @@
+use leptos::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Backlink {
+    pub source_collection: String,
+    pub source_href: String,
+    pub source_title: String,
+    pub context: String,
+}
+
+#[server(GetBacklinks, "/api")]
+#[cfg_attr(feature = "ssr", tracing::instrument(skip_all))]
+pub async fn get_backlinks(post_href: String) -> Result<Vec<Backlink>, ServerFnError> {
+    let result = tokio::task::spawn_blocking(move || {
+        crate::content::content().backlinks.get(&post_href).cloned().unwrap_or_default()
+    })
+    .await;
+
+    match result {
+        Ok(backlinks) => Ok(backlinks),
+        Err(e) => Err(ServerFnError::new(format!("Threading error: {}", e))),
+    }
+}
+
+cfg_if::cfg_if! {
+    if #[cfg(feature = "ssr")] {
+        use crate::config::site_config;
+        use crate::server_functions::posts::{strip_html, Posts};
+        use std::collections::{HashMap, HashSet};
+
+        const MAX_CONTEXT_LEN: usize = 240;
+
+        // Every post linking to each href, with the sentence around its first link. Built once
+        // per change to the posts, by `crate::content`.
+        pub fn build_backlinks(posts: &Posts) -> HashMap<String, Vec<Backlink>> {
+            let mut backlinks: HashMap<String, Vec<Backlink>> = HashMap::new();
+            for collection in &site_config().collections {
+                for post in posts.get(&collection.name).into_iter().flatten() {
+                    let source_href = collection.href(&post.post_metadata.create_href());
+                    let mut linked = HashSet::new();
+                    for (target, context) in link_contexts(&post.post_content) {
+                        if target == source_href || !linked.insert(target.clone()) {
+                            continue;
+                        }
+                        backlinks.entry(target).or_default().push(Backlink {
+                            source_collection: collection.name.clone(),
+                            source_href: source_href.clone(),
+                            source_title: post.post_metadata.title.clone(),
+                            context,
+                        });
+                    }
+                }
+            }
+            for links in backlinks.values_mut() {
+                links.sort_by(|a, b| a.source_title.cmp(&b.source_title));
+            }
+            backlinks
+        }
+
+        // Links to posts of any collection paired with the sentence of the paragraph they appear in.
+        pub fn link_contexts(html: &str) -> Vec<(String, String)> {
+            let block_re = regex::Regex::new(r"(?s)<(?:p|li)>(.*?)</(?:p|li)>").unwrap();
+            let anchor_re = regex::Regex::new(r##"(?s)<a href="(/[^"#]+)(?:#[^"]*)?"[^>]*>(.*?)</a>"##).unwrap();
+            let config = site_config();
+
+            let mut contexts = Vec::new();
+            for block in block_re.captures_iter(html) {
+                let block_html = &block[1];
+                let text = strip_html(block_html);
+                for anchor in anchor_re.captures_iter(block_html) {
+                    let target = anchor[1].to_string();
+                    if config.collection_for_href(&target).is_none() {
+                        continue;
+                    }
+                    let anchor_text = strip_html(&anchor[2]);
+                    let sentence = sentences(&text)
+                        .into_iter()
+                        .find(|sentence| sentence.contains(anchor_text.as_str()))
+                        .unwrap_or(text.trim());
+                    contexts.push((target, truncate(sentence, MAX_CONTEXT_LEN)));
+                }
+            }
+            contexts
+        }
+
+        fn sentences(text: &str) -> Vec<&str> {
+            let mut sentences = Vec::new();
+            let mut start = 0;
+            let mut chars = text.char_indices().peekable();
+            while let Some((i, c)) = chars.next() {
+                let at_boundary = matches!(c, '.' | '!' | '?')
+                    && chars.peek().map_or(true, |(_, next)| next.is_whitespace());
+                if at_boundary {
+                    let end = i + c.len_utf8();
+                    sentences.push(text[start..end].trim());
+                    start = end;
+                }
+            }
+            if start < text.len() {
+                sentences.push(text[start..].trim());
+            }
+            sentences.retain(|sentence| !sentence.is_empty());
+            sentences
+        }
+
+        fn truncate(text: &str, max_len: usize) -> String {
+            let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+            if text.chars().count() <= max_len {
+                text
+            } else {
+                format!("{}…", text.chars().take(max_len).collect::<String>().trim_end())
+            }
+        }
+
+        #[cfg(test)]
+        mod tests {
+            use super::*;
+
+            #[test]
+            fn link_context_is_the_sentence_with_the_link() {
+                let html = r#"<p>Padding matters. See <a href="/blog/set-2#cbc">the CBC attack</a> for details! Done.</p>"#;
+                assert_eq!(
+                    link_contexts(html),
+                    [("/blog/set-2".to_string(), "See the CBC attack for details!".to_string())]
+                );
+            }
+
+            #[test]
+            fn only_links_to_posts_count() {
+                let html = r#"<li>Tagged <a href="/tags/rust">rust</a>, see <a href="/projects/pe-parser">the parser</a>.</li>"#;
+                let targets: Vec<_> = link_contexts(html).into_iter().map(|(target, _)| target).collect();
+                assert_eq!(targets, ["/projects/pe-parser"]);
+            }
+
+            #[test]
+            fn long_context_is_truncated() {
+                assert_eq!(truncate("one  two\nthree", 100), "one two three");
+                assert_eq!(truncate("abcdef ghij", 7), "abcdef…");
+            }
+        }
+    }
+}
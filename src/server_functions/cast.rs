@@ -0,0 +1,121 @@
+use leptos::*;
+use serde::{Deserialize, Serialize};
+
+// A terminal session recording, reduced to what the player needs: the terminal size and the
+// output written to it, with long idle gaps shortened.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Cast {
+    pub width: usize,
+    pub height: usize,
+    // Seconds.
+    pub duration: f64,
+    // Seconds since the start, and the text written at that time.
+    pub events: Vec<(f64, String)>,
+}
+
+#[server(GetCast, "/api")]
+#[cfg_attr(feature = "ssr", tracing::instrument(skip_all))]
+pub async fn get_cast(src: String) -> Result<Cast, ServerFnError> {
+    let result = tokio::task::spawn_blocking(move || read_cast(&src)).await;
+    match result {
+        Ok(cast) => cast.map_err(ServerFnError::new),
+        Err(e) => Err(ServerFnError::new(format!("Threading error: {}", e))),
+    }
+}
+
+cfg_if::cfg_if! {
+    if #[cfg(feature = "ssr")] {
+        use std::path::Path;
+
+        // Recordings are served as static files too, so they live with the other public assets.
+        pub const PUBLIC_DIR: &str = "public";
+
+        // Pauses longer than this are cut short, unless the recording sets its own limit.
+        const MAX_IDLE_SECONDS: f64 = 2.0;
+
+        // Reads an asciicast v2 file by its url, e.g. `/casts/rop.cast`.
+        pub fn read_cast(src: &str) -> Result<Cast, String> {
+            let relative = src.trim_start_matches('/');
+            if !src.starts_with('/') || !src.ends_with(".cast") || relative.split('/').any(|part| part == "..") {
+                return Err(format!("invalid recording path `{}`", src));
+            }
+            let text = std::fs::read_to_string(Path::new(PUBLIC_DIR).join(relative)).map_err(|e| e.to_string())?;
+            parse_cast(&text)
+        }
+
+        // The first line is a json header, every other line an `[time, type, data]` event.
+        // Only output (`"o"`) events are kept.
+        fn parse_cast(text: &str) -> Result<Cast, String> {
+            #[derive(Deserialize)]
+            struct Header {
+                version: u32,
+                width: usize,
+                height: usize,
+                idle_time_limit: Option<f64>,
+            }
+
+            let mut lines = text.lines().filter(|line| !line.trim().is_empty());
+            let header: Header = serde_json::from_str(lines.next().ok_or("empty recording")?)
+                .map_err(|e| format!("invalid header: {}", e))?;
+            if header.version != 2 {
+                return Err(format!("unsupported asciicast version {}", header.version));
+            }
+            let idle_limit = header.idle_time_limit.unwrap_or(MAX_IDLE_SECONDS);
+
+            let mut events = Vec::new();
+            let (mut recorded, mut played) = (0.0, 0.0);
+            for (number, line) in lines.enumerate() {
+                let (time, kind, data): (f64, String, String) =
+                    serde_json::from_str(line).map_err(|e| format!("invalid event on line {}: {}", number + 2, e))?;
+                if kind != "o" {
+                    continue;
+                }
+                played += (time - recorded).clamp(0.0, idle_limit);
+                recorded = time;
+                events.push((played, data));
+            }
+            Ok(Cast {
+                width: header.width,
+                height: header.height,
+                duration: played,
+                events,
+            })
+        }
+
+        #[cfg(test)]
+        mod tests {
+            use super::*;
+
+            #[test]
+            fn keeps_output_and_shortens_pauses() {
+                let cast = parse_cast(concat!(
+                    r#"{"version": 2, "width": 80, "height": 24}"#,
+                    "\n[0.5, \"o\", \"$ \"]\n[1.0, \"i\", \"l\"]\n\n[10.0, \"o\", \"ls\\r\\n\"]\n[10.25, \"o\", \"a.out\"]\n",
+                ))
+                .unwrap();
+                assert_eq!((cast.width, cast.height), (80, 24));
+                assert_eq!(
+                    cast.events,
+                    [(0.5, "$ ".to_string()), (2.5, "ls\r\n".to_string()), (2.75, "a.out".to_string())]
+                );
+                assert_eq!(cast.duration, 2.75);
+            }
+
+            #[test]
+            fn recordings_set_their_own_idle_limit() {
+                let cast = parse_cast("{\"version\": 2, \"width\": 80, \"height\": 24, \"idle_time_limit\": 0.5}\n[3, \"o\", \"x\"]").unwrap();
+                assert_eq!(cast.duration, 0.5);
+            }
+
+            #[test]
+            fn invalid_recordings_are_rejected() {
+                assert_eq!(parse_cast("").unwrap_err(), "empty recording");
+                assert!(parse_cast("{\"version\": 1, \"width\": 80, \"height\": 24}").unwrap_err().contains("version 1"));
+                let error = parse_cast("{\"version\": 2, \"width\": 80, \"height\": 24}\n[0.1, \"o\"]").unwrap_err();
+                assert!(error.contains("line 2"), "{}", error);
+                assert!(read_cast("/casts/../../etc/passwd.cast").is_err());
+                assert!(read_cast("casts/rop.cast").is_err());
+            }
+        }
+    }
+}
@@ -0,0 +1,353 @@
+use leptos::*;
+use serde::{Deserialize, Serialize};
+
+// Coordinate space of the server-side layout, matching the aspect ratio of the graph panel.
+pub const LAYOUT_WIDTH: f64 = 600.0;
+pub const LAYOUT_HEIGHT: f64 = 220.0;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct GraphNode {
+    pub id: String,
+    pub title: String,
+    pub href: String,
+    pub collection: String,
+    pub date: String,
+    pub tags: Vec<String>,
+    // Number of edges touching this node in the full graph.
+    pub degree: usize,
+    pub x: f64,
+    pub y: f64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct GraphEdge {
+    pub source: String,
+    pub target: String,
+    pub label: String,
+    pub shared_tags: Vec<String>,
+    // Internal links between the two posts, counted in both directions.
+    pub link_count: usize,
+    pub weight: f64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct GraphData {
+    pub nodes: Vec<GraphNode>,
+    pub edges: Vec<GraphEdge>,
+}
+
+// Every argument is optional: with none set the full graph is returned.
+// `center` restricts the graph to the posts within `depth` hops (default 1) of that href.
+#[server(GetGraphData, "/api")]
+#[cfg_attr(feature = "ssr", tracing::instrument(skip_all))]
+pub async fn get_graph_data(
+    tag: Option<String>,
+    collection: Option<String>,
+    center: Option<String>,
+    depth: Option<usize>,
+) -> Result<GraphData, ServerFnError> {
+    let result = tokio::task::spawn_blocking(move || {
+        let content = crate::content::content();
+        let mut graph = filter_graph(&content.graph, tag.as_deref(), collection.as_deref(), center.as_deref(), depth);
+        layout(&mut graph, LAYOUT_WIDTH, LAYOUT_HEIGHT);
+        graph
+    })
+    .await;
+
+    match result {
+        Ok(data) => Ok(data),
+        Err(e) => Err(ServerFnError::new(format!("Threading error: {}", e))),
+    }
+}
+
+cfg_if::cfg_if! {
+    if #[cfg(feature = "ssr")] {
+        use crate::config::site_config;
+        use crate::server_functions::posts::{internal_links, Posts};
+        use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
+
+        const LAYOUT_ITERATIONS: usize = 300;
+        const LAYOUT_PADDING: f64 = 28.0;
+        // Pull towards the centre, relative to the distance from it.
+        const LAYOUT_GRAVITY: f64 = 0.3;
+
+        // The graph of every post in a collection with `in_graph`, built in a single pass over
+        // the posts. `crate::content` keeps it until the posts change.
+        pub fn build_graph(posts: &Posts) -> GraphData {
+
+            let mut nodes = Vec::new();
+            let mut links: Vec<(String, Vec<String>)> = Vec::new();
+            for collection in site_config().collections.iter().filter(|collection| collection.in_graph) {
+                for post in posts.get(&collection.name).into_iter().flatten() {
+                    let href = collection.href(&post.post_metadata.create_href());
+                    links.push((href.clone(), internal_links(&post.post_content)));
+                    nodes.push(GraphNode {
+                        id: href.clone(),
+                        title: post.post_metadata.title.clone(),
+                        href,
+                        collection: collection.name.clone(),
+                        date: post.post_metadata.date.clone(),
+                        tags: post.post_metadata.tags.clone(),
+                        degree: 0,
+                        x: 0.0,
+                        y: 0.0,
+                    });
+                }
+            }
+            nodes.sort_by(|a, b| a.id.cmp(&b.id));
+
+            // Undirected edges keyed by (smaller id, larger id) so each pair appears once.
+            let mut pairs: BTreeMap<(String, String), (Vec<String>, usize)> = BTreeMap::new();
+            for (i, a) in nodes.iter().enumerate() {
+                for b in &nodes[i + 1..] {
+                    let shared: Vec<String> = a.tags.iter().filter(|tag| b.tags.contains(tag)).cloned().collect();
+                    if !shared.is_empty() {
+                        pairs.entry(edge_key(&a.id, &b.id)).or_default().0 = shared;
+                    }
+                }
+            }
+            let ids: HashSet<&str> = nodes.iter().map(|node| node.id.as_str()).collect();
+            for (source, targets) in &links {
+                for target in targets {
+                    if target != source && ids.contains(target.as_str()) {
+                        pairs.entry(edge_key(source, target)).or_default().1 += 1;
+                    }
+                }
+            }
+
+            let edges: Vec<GraphEdge> = pairs
+                .into_iter()
+                .map(|((source, target), (shared_tags, link_count))| GraphEdge {
+                    label: if shared_tags.is_empty() { "link".to_string() } else { shared_tags.join(", ") },
+                    weight: (shared_tags.len() + link_count) as f64,
+                    source,
+                    target,
+                    shared_tags,
+                    link_count,
+                })
+                .collect();
+
+            for node in &mut nodes {
+                node.degree = edges.iter().filter(|e| e.source == node.id || e.target == node.id).count();
+            }
+
+            GraphData { nodes, edges }
+        }
+
+        fn edge_key(a: &str, b: &str) -> (String, String) {
+            if a < b { (a.to_string(), b.to_string()) } else { (b.to_string(), a.to_string()) }
+        }
+
+        pub fn filter_graph(
+            graph: &GraphData,
+            tag: Option<&str>,
+            collection: Option<&str>,
+            center: Option<&str>,
+            depth: Option<usize>,
+        ) -> GraphData {
+            let mut keep: HashSet<&str> = graph
+                .nodes
+                .iter()
+                .filter(|node| tag.map_or(true, |tag| node.tags.iter().any(|t| t == tag)))
+                .filter(|node| collection.map_or(true, |collection| node.collection == collection))
+                .map(|node| node.id.as_str())
+                .collect();
+
+            if let Some(center) = center {
+                let mut adjacency: HashMap<&str, Vec<&str>> = HashMap::new();
+                for edge in &graph.edges {
+                    adjacency.entry(edge.source.as_str()).or_default().push(edge.target.as_str());
+                    adjacency.entry(edge.target.as_str()).or_default().push(edge.source.as_str());
+                }
+
+                let max_depth = depth.unwrap_or(1);
+                let mut reached = HashSet::new();
+                let mut queue = VecDeque::new();
+                if keep.contains(center) {
+                    reached.insert(center);
+                    queue.push_back((center, 0));
+                }
+                while let Some((id, distance)) = queue.pop_front() {
+                    if distance == max_depth {
+                        continue;
+                    }
+                    for &next in adjacency.get(id).into_iter().flatten() {
+                        if keep.contains(next) && reached.insert(next) {
+                            queue.push_back((next, distance + 1));
+                        }
+                    }
+                }
+                keep = reached;
+            }
+
+            GraphData {
+                nodes: graph.nodes.iter().filter(|node| keep.contains(node.id.as_str())).cloned().collect(),
+                edges: graph
+                    .edges
+                    .iter()
+                    .filter(|edge| keep.contains(edge.source.as_str()) && keep.contains(edge.target.as_str()))
+                    .cloned()
+                    .collect(),
+            }
+        }
+
+        // Fruchterman-Reingold force-directed layout, deterministic for a given graph.
+        pub fn layout(graph: &mut GraphData, width: f64, height: f64) {
+            let n = graph.nodes.len();
+            if n == 0 {
+                return;
+            }
+            let (cx, cy) = (width / 2.0, height / 2.0);
+            if n == 1 {
+                graph.nodes[0].x = cx;
+                graph.nodes[0].y = cy;
+                return;
+            }
+
+            let index: HashMap<&str, usize> = graph
+                .nodes
+                .iter()
+                .enumerate()
+                .map(|(i, node)| (node.id.as_str(), i))
+                .collect();
+            let edges: Vec<(usize, usize, f64)> = graph
+                .edges
+                .iter()
+                .filter_map(|edge| Some((*index.get(edge.source.as_str())?, *index.get(edge.target.as_str())?, edge.weight)))
+                .collect();
+
+            let inner_width = width - 2.0 * LAYOUT_PADDING;
+            let inner_height = height - 2.0 * LAYOUT_PADDING;
+            let k = 0.6 * (inner_width * inner_height / n as f64).sqrt();
+
+            let mut positions: Vec<(f64, f64)> = (0..n)
+                .map(|i| {
+                    let angle = 2.0 * std::f64::consts::PI * i as f64 / n as f64;
+                    (cx + inner_width / 2.0 * angle.cos(), cy + inner_height / 2.0 * angle.sin())
+                })
+                .collect();
+
+            let mut temperature = inner_width / 10.0;
+            let cooling = temperature / LAYOUT_ITERATIONS as f64;
+            for _ in 0..LAYOUT_ITERATIONS {
+                let mut displacement = vec![(0.0, 0.0); n];
+
+                for i in 0..n {
+                    for j in (i + 1)..n {
+                        let (dx, dy) = (positions[i].0 - positions[j].0, positions[i].1 - positions[j].1);
+                        let distance = (dx * dx + dy * dy).sqrt().max(0.01);
+                        let force = k * k / distance;
+                        let (fx, fy) = (dx / distance * force, dy / distance * force);
+                        displacement[i].0 += fx;
+                        displacement[i].1 += fy;
+                        displacement[j].0 -= fx;
+                        displacement[j].1 -= fy;
+                    }
+                }
+
+                for &(i, j, weight) in &edges {
+                    let (dx, dy) = (positions[i].0 - positions[j].0, positions[i].1 - positions[j].1);
+                    let distance = (dx * dx + dy * dy).sqrt().max(0.01);
+                    let force = distance * distance / k * weight.max(1.0).sqrt();
+                    let (fx, fy) = (dx / distance * force, dy / distance * force);
+                    displacement[i].0 -= fx;
+                    displacement[i].1 -= fy;
+                    displacement[j].0 += fx;
+                    displacement[j].1 += fy;
+                }
+
+                for (position, (dx, dy)) in positions.iter_mut().zip(displacement) {
+                    // Gravity keeps disconnected posts from drifting to the walls.
+                    let dx = dx + (cx - position.0) * LAYOUT_GRAVITY;
+                    let dy = dy + (cy - position.1) * LAYOUT_GRAVITY * inner_width / inner_height;
+                    let length = (dx * dx + dy * dy).sqrt().max(0.01);
+                    let step = length.min(temperature);
+                    position.0 = (position.0 + dx / length * step).clamp(LAYOUT_PADDING, width - LAYOUT_PADDING);
+                    position.1 = (position.1 + dy / length * step).clamp(LAYOUT_PADDING, height - LAYOUT_PADDING);
+                }
+
+                temperature = (temperature - cooling).max(1.0);
+            }
+
+            for (node, (x, y)) in graph.nodes.iter_mut().zip(positions) {
+                node.x = (x * 10.0).round() / 10.0;
+                node.y = (y * 10.0).round() / 10.0;
+            }
+        }
+
+        #[cfg(test)]
+        mod tests {
+            use super::*;
+
+            fn node(id: &str) -> GraphNode {
+                GraphNode {
+                    id: id.to_string(),
+                    title: id.to_string(),
+                    href: id.to_string(),
+                    collection: "blog".to_string(),
+                    date: "2024-01-01".to_string(),
+                    tags: Vec::new(),
+                    degree: 0,
+                    x: 0.0,
+                    y: 0.0,
+                }
+            }
+
+            fn edge(source: &str, target: &str) -> GraphEdge {
+                GraphEdge {
+                    source: source.to_string(),
+                    target: target.to_string(),
+                    label: "link".to_string(),
+                    shared_tags: Vec::new(),
+                    link_count: 1,
+                    weight: 1.0,
+                }
+            }
+
+            fn graph(size: usize) -> GraphData {
+                let ids: Vec<String> = (0..size).map(|i| format!("/blog/{}", i)).collect();
+                GraphData {
+                    nodes: ids.iter().map(|id| node(id)).collect(),
+                    edges: ids.windows(2).map(|pair| edge(&pair[0], &pair[1])).collect(),
+                }
+            }
+
+            #[test]
+            fn layout_stays_inside_the_padding() {
+                for size in [2, 3, 12, 40] {
+                    let mut graph = graph(size);
+                    layout(&mut graph, LAYOUT_WIDTH, LAYOUT_HEIGHT);
+                    for node in &graph.nodes {
+                        assert!((LAYOUT_PADDING..=LAYOUT_WIDTH - LAYOUT_PADDING).contains(&node.x), "{} x={}", size, node.x);
+                        assert!((LAYOUT_PADDING..=LAYOUT_HEIGHT - LAYOUT_PADDING).contains(&node.y), "{} y={}", size, node.y);
+                    }
+                }
+            }
+
+            #[test]
+            fn layout_is_deterministic() {
+                let (mut a, mut b) = (graph(8), graph(8));
+                layout(&mut a, LAYOUT_WIDTH, LAYOUT_HEIGHT);
+                layout(&mut b, LAYOUT_WIDTH, LAYOUT_HEIGHT);
+                let positions = |graph: &GraphData| graph.nodes.iter().map(|node| (node.x, node.y)).collect::<Vec<_>>();
+                assert_eq!(positions(&a), positions(&b));
+            }
+
+            #[test]
+            fn single_node_is_centred() {
+                let mut graph = graph(1);
+                layout(&mut graph, LAYOUT_WIDTH, LAYOUT_HEIGHT);
+                assert_eq!((graph.nodes[0].x, graph.nodes[0].y), (LAYOUT_WIDTH / 2.0, LAYOUT_HEIGHT / 2.0));
+            }
+
+            #[test]
+            fn filter_keeps_neighbours_within_depth() {
+                let graph = graph(5);
+                let ids = |graph: GraphData| graph.nodes.into_iter().map(|node| node.id).collect::<Vec<_>>();
+                assert_eq!(ids(filter_graph(&graph, None, None, Some("/blog/2"), None)), ["/blog/1", "/blog/2", "/blog/3"]);
+                assert_eq!(ids(filter_graph(&graph, None, None, Some("/blog/0"), Some(2))), ["/blog/0", "/blog/1", "/blog/2"]);
+                assert!(ids(filter_graph(&graph, None, None, Some("/blog/missing"), None)).is_empty());
+            }
+        }
+    }
+}
@@ -1 +1,6 @@
+pub mod backlinks;
+pub mod cast;
+pub mod graph;
 pub mod posts;
+pub mod related;
+pub mod stats;
@@ -1,7 +1,7 @@
 use leptos::*;
+use crate::components::image::ResponsiveImage;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
-use std::collections::HashSet;
+use std::collections::BTreeMap;
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct TocItem {
@@ -26,6 +26,20 @@ pub struct PostMetadata {
     pub seo_keywords: Option<String>,
     #[serde(default)]
     pub tags: Vec<String>,
+    #[serde(default)]
+    pub series: Option<String>,
+    // Set on posts that were taken down: their url answers 410 Gone, or redirects to
+    // `replaced_by`, and they are left out of every listing.
+    #[serde(default)]
+    pub removed: bool,
+    #[serde(default)]
+    pub removed_reason: Option<String>,
+    // Href of the post that supersedes this one, e.g. `/blog/new-post`.
+    #[serde(default)]
+    pub replaced_by: Option<String>,
+    // Resized copies of `image_path`, filled in when the posts are loaded.
+    #[serde(default)]
+    pub cover: Option<ResponsiveImage>,
 }
 
 impl PostMetadata {
@@ -41,6 +55,9 @@ pub struct Post {
     pub post_metadata: PostMetadata,
     pub post_content: PostContent,
     pub toc: Vec<TocItem>,
+    // Problems found while rendering the markdown, e.g. unresolved references.
+    #[serde(skip)]
+    pub content_errors: Vec<String>,
 }
 
 impl Post {
@@ -49,170 +66,452 @@ impl Post {
             post_metadata,
             post_content,
             toc,
+            content_errors: Vec::new(),
         }
     }
 }
 
-#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Hash, Clone, Copy)]
-pub enum PostType {
-    Blog,
-    Project,
-    Notes,
-    Writing,
+// What is left of a removed post, enough to explain the removal at its old url.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Tombstone {
+    pub href: String,
+    pub title: String,
+    pub reason: Option<String>,
+    pub replaced_by: Option<String>,
 }
 
-impl std::fmt::Display for PostType {
-    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-        match self {
-            PostType::Blog => write!(f, "blog"),
-            PostType::Project => write!(f, "projects"),
-            PostType::Notes => write!(f, "notes"),
-            PostType::Writing => write!(f, "writing"),
-        }
-    }
-}
+// Posts of every collection, keyed by collection name. Ordered so the serialized map, and
+// with it the rendered page and its ETag, is the same on every request.
+pub type Posts = BTreeMap<String, Vec<Post>>;
 
 #[server(GetPosts, "/api")]
-pub async fn get_posts() -> Result<HashMap<PostType, Vec<Post>>, ServerFnError> {
-    let result = tokio::task::spawn_blocking(move || {
-        let mut post_paths = HashMap::new();
-        post_paths.insert(PostType::Blog, "posts/blog");
-        post_paths.insert(PostType::Project, "posts/projects");
-        post_paths.insert(PostType::Writing, "posts/writing");
-
-        let mut all_posts = HashMap::new();
-
-        for (post_type, path) in post_paths {
-            let posts = process_posts(path);
-            all_posts.insert(post_type, posts);
-        }
-        all_posts
-    })
-    .await;
+#[cfg_attr(feature = "ssr", tracing::instrument(skip_all))]
+pub async fn get_posts() -> Result<Posts, ServerFnError> {
+    // Spans don't follow the work onto the blocking pool by themselves.
+    let span = tracing::Span::current();
+    let result = tokio::task::spawn_blocking(move || span.in_scope(|| crate::content::content().posts.clone())).await;
     match result {
         Ok(posts) => Ok(posts),
         Err(e) => Err(ServerFnError::new(format!("Threading error: {}", e))),
     }
 }
-#[server(GetLastUpdate, "/api")]
-pub async fn get_last_update() -> Result<String, ServerFnError> {
-    Ok(std::env::var("LAST_UPDATED").unwrap_or_else(|_| "Date not Found".to_string()))
+#[server(GetTombstones, "/api")]
+#[cfg_attr(feature = "ssr", tracing::instrument(skip_all))]
+pub async fn get_tombstones() -> Result<Vec<Tombstone>, ServerFnError> {
+    let result = tokio::task::spawn_blocking(|| crate::content::content().index.tombstones.clone()).await;
+    match result {
+        Ok(tombstones) => Ok(tombstones),
+        Err(e) => Err(ServerFnError::new(format!("Threading error: {}", e))),
+    }
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
-pub struct GraphNode {
-    pub id: String,
-    pub title: String,
-    pub href: String,
-    pub tags: Vec<String>,
+#[server(GetLastUpdate, "/api")]
+#[cfg_attr(feature = "ssr", tracing::instrument(skip_all))]
+pub async fn get_last_update() -> Result<String, ServerFnError> {
+    Ok(crate::build_info::build_info().last_updated.clone())
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
-pub struct GraphEdge {
-    pub source: String,
-    pub target: String,
-    pub label: String,
-}
+cfg_if::cfg_if! {
+    if #[cfg(feature = "ssr")] {
+        use std::{
+            fs::{self, DirEntry},
+            path::Path,
+        };
+        use crate::components::cast::{cast_html, CastMeta, Terminal};
+        use crate::components::image::POST_IMAGE_SIZES;
+        use crate::config::{is_dev_mode, site_config, SortOrder};
+        use chrono::NaiveDate;
+        use pulldown_cmark::{CowStr, Event, LinkType, Tag, TagEnd};
+
+        #[derive(Debug, Clone)]
+        pub struct IndexEntry {
+            pub href: String,
+            pub title: String,
+            // Collection name and file name of the markdown file, e.g. `blog/set2.md`.
+            pub file: String,
+            // Ids of every heading in the post, as generated by `heading_id`.
+            pub headings: Vec<TocItem>,
+        }
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
-pub struct GraphData {
-    pub nodes: Vec<GraphNode>,
-    pub edges: Vec<GraphEdge>,
-}
+        impl IndexEntry {
+            fn stem(&self) -> &str {
+                Path::new(&self.file)
+                    .file_stem()
+                    .and_then(|stem| stem.to_str())
+                    .unwrap_or_default()
+            }
 
-#[server(GetGraphData, "/api")]
-pub async fn get_graph_data() -> Result<GraphData, ServerFnError> {
-    let result = tokio::task::spawn_blocking(move || {
-        let mut post_paths = HashMap::new();
-        post_paths.insert(PostType::Blog, "posts/blog");
-        post_paths.insert(PostType::Writing, "posts/writing");
-
-        let mut nodes = Vec::new();
-        let mut edges = Vec::new();
-
-        for (post_type, path) in &post_paths {
-            let dir = std::path::Path::new(path);
-            if !dir.exists() { continue; }
-            let posts = process_posts(dir);
-            for post in &posts {
-                let href = format!("/{}/{}", post_type, post.post_metadata.create_href());
-                nodes.push(GraphNode {
-                    id: href.clone(),
-                    title: post.post_metadata.title.clone(),
-                    href,
-                    tags: post.post_metadata.tags.clone(),
-                });
+            pub fn heading(&self, heading: &str) -> Option<&TocItem> {
+                let id = heading_id(heading);
+                self.headings.iter().find(|item| item.id == id)
             }
         }
 
-        // Edges from shared tags
-        let mut tag_map: HashMap<String, Vec<String>> = HashMap::new();
-        for node in &nodes {
-            for tag in &node.tags {
-                tag_map.entry(tag.clone()).or_default().push(node.id.clone());
-            }
+        // Every post known to the site, built from frontmatter and headings only so that post
+        // bodies can link to each other before any of them is rendered.
+        #[derive(Debug, Clone, Default)]
+        pub struct PostIndex {
+            pub entries: Vec<IndexEntry>,
+            // Removed posts. Links to them are reported like links to missing posts.
+            pub tombstones: Vec<Tombstone>,
         }
-        let mut edge_set: HashSet<(String, String)> = HashSet::new();
-        for (_tag, ids) in &tag_map {
-            for i in 0..ids.len() {
-                for j in (i + 1)..ids.len() {
-                    let a = ids[i].clone();
-                    let b = ids[j].clone();
-                    let key = if a < b { (a.clone(), b.clone()) } else { (b.clone(), a.clone()) };
-                    if edge_set.insert(key) {
-                        edges.push(GraphEdge {
-                            source: a,
-                            target: b,
-                            label: _tag.clone(),
+
+        impl PostIndex {
+            pub fn build() -> Self {
+                use gray_matter::engine::YAML;
+                use gray_matter::Matter;
+
+                let matter = Matter::<YAML>::new();
+                let mut entries = Vec::new();
+                let mut tombstones = Vec::new();
+                for collection in &site_config().collections {
+                    if !Path::new(&collection.dir).exists() {
+                        continue;
+                    }
+                    for entry in get_posts_file(&collection.dir) {
+                        let file = format!("{}/{}", collection.name, entry.file_name().to_string_lossy());
+                        let Some(content) = read_post_content(entry) else { continue };
+                        let Some(parsed) = matter.parse_with_struct::<PostMetadata>(&content) else {
+                            continue;
+                        };
+                        if parsed.data.removed {
+                            tombstones.push(Tombstone {
+                                href: collection.href(&parsed.data.create_href()),
+                                title: parsed.data.title,
+                                reason: parsed.data.removed_reason,
+                                replaced_by: parsed.data.replaced_by,
+                            });
+                            continue;
+                        }
+                        entries.push(IndexEntry {
+                            href: collection.href(&parsed.data.create_href()),
+                            title: parsed.data.title,
+                            file,
+                            headings: collect_headings(&parsed.content),
                         });
                     }
                 }
+                Self { entries, tombstones }
+            }
+
+            // `[[slug]]` matches either the file name without extension or the title slug.
+            pub fn resolve_slug(&self, slug: &str) -> Option<&IndexEntry> {
+                let slug = slug.trim().to_lowercase();
+                self.entries
+                    .iter()
+                    .find(|entry| entry.stem() == slug)
+                    .or_else(|| self.entries.iter().find(|entry| entry.href.ends_with(&format!("/{}", slug))))
+            }
+
+            // Resolves relative markdown links such as `../blog/set2.md` or `set2.md`.
+            pub fn resolve_path(&self, path: &str) -> Option<&IndexEntry> {
+                let path = path
+                    .split('/')
+                    .filter(|part| !part.is_empty() && *part != "." && *part != "..")
+                    .collect::<Vec<_>>()
+                    .join("/");
+                self.entries
+                    .iter()
+                    .find(|entry| entry.file == path || entry.file.ends_with(&format!("/{}", path)))
+            }
+        }
+
+        // Id used for the `id` attribute of a rendered heading, e.g. "Challenge 12!" -> "challenge-12".
+        pub fn heading_id(text: &str) -> String {
+            text.to_lowercase()
+                .chars()
+                .map(|c| if c.is_alphanumeric() || c == ' ' { c } else { ' ' })
+                .collect::<String>()
+                .split_whitespace()
+                .collect::<Vec<_>>()
+                .join("-")
+        }
+
+        fn collect_headings(markdown: &str) -> Vec<TocItem> {
+            use pulldown_cmark::{Options, Parser};
+
+            let mut headings = Vec::new();
+            let mut current: Option<(u8, String)> = None;
+            for event in Parser::new_ext(markdown, Options::ENABLE_HEADING_ATTRIBUTES) {
+                match event {
+                    Event::Start(Tag::Heading { level, .. }) => current = Some((level as u8, String::new())),
+                    Event::Text(text) | Event::Code(text) => {
+                        if let Some((_, heading)) = current.as_mut() {
+                            heading.push_str(&text);
+                        }
+                    }
+                    Event::End(TagEnd::Heading(_)) => {
+                        if let Some((level, text)) = current.take() {
+                            headings.push(TocItem { id: heading_id(&text), text, level });
+                        }
+                    }
+                    _ => {}
+                }
             }
+            headings
         }
 
-        // Edges from internal links in post content
-        let link_re = regex::Regex::new(r#"href="/(blog|writing|projects)/([^"]+)""#).unwrap();
-        for (post_type, path) in &post_paths {
-            let dir = std::path::Path::new(path);
-            if !dir.exists() { continue; }
-            let posts = process_posts(dir);
-            for post in &posts {
-                let source_href = format!("/{}/{}", post_type, post.post_metadata.create_href());
-                for cap in link_re.captures_iter(&post.post_content) {
-                    let target_href = format!("/{}/{}", &cap[1], &cap[2]);
-                    if nodes.iter().any(|n| n.id == target_href) {
-                        let key = if source_href < target_href {
-                            (source_href.clone(), target_href.clone())
-                        } else {
-                            (target_href.clone(), source_href.clone())
+        // Rewrites links that point at other posts by file or by wiki-style reference into
+        // regular `/<collection>/<slug>` hrefs. Text inside code blocks is left untouched.
+        //
+        //   [[set2]]                  -> link titled with the post title
+        //   [[set2|the set 2 post]]   -> link with a custom label
+        //   [[set2#challenge 12]]     -> link to a heading, titled with the heading text
+        //
+        // References that can't be resolved are pushed to `errors`; in dev mode they are
+        // rendered as visibly broken links, otherwise only their label is kept.
+        fn resolve_post_links<'a>(
+            event: Event<'a>,
+            in_code_block: &mut bool,
+            index: &PostIndex,
+            errors: &mut Vec<String>,
+        ) -> Vec<Event<'a>> {
+            match event {
+                Event::Start(Tag::CodeBlock(_)) => {
+                    *in_code_block = true;
+                    vec![event]
+                }
+                Event::End(TagEnd::CodeBlock) => {
+                    *in_code_block = false;
+                    vec![event]
+                }
+                Event::Start(Tag::Link { link_type, dest_url, title, id })
+                    if is_markdown_path(&dest_url) =>
+                {
+                    let (path, fragment) = match dest_url.split_once('#') {
+                        Some((path, fragment)) => (path, Some(fragment)),
+                        None => (dest_url.as_ref(), None),
+                    };
+                    let dest_url = match index.resolve_path(path) {
+                        Some(entry) => match fragment {
+                            Some(fragment) => CowStr::from(format!("{}#{}", entry.href, fragment)),
+                            None => CowStr::from(entry.href.clone()),
+                        },
+                        None => {
+                            errors.push(format!("unresolved link to `{}`", dest_url));
+                            dest_url
+                        }
+                    };
+                    vec![Event::Start(Tag::Link { link_type, dest_url, title, id })]
+                }
+                Event::Text(text) if !*in_code_block && text.contains("[[") => {
+                    let wiki_re = regex::Regex::new(
+                        r"\[\[([^\[\]|#]+)(?:#([^\[\]|]+))?(?:\|([^\[\]]+))?\]\]"
+                    ).unwrap();
+                    let mut events = Vec::new();
+                    let mut last = 0;
+                    for cap in wiki_re.captures_iter(&text) {
+                        let whole = cap.get(0).unwrap();
+                        if whole.start() > last {
+                            events.push(Event::Text(text[last..whole.start()].to_string().into()));
+                        }
+                        last = whole.end();
+
+                        let slug = cap[1].trim();
+                        let heading = cap.get(2).map(|m| m.as_str().trim());
+                        let label = cap.get(3).map(|m| m.as_str().trim().to_string());
+
+                        let Some(entry) = index.resolve_slug(slug) else {
+                            errors.push(format!("unresolved reference `{}`", whole.as_str()));
+                            events.extend(broken_link(label.unwrap_or_else(|| slug.to_string()), whole.as_str()));
+                            continue;
                         };
-                        if edge_set.insert(key) {
-                            edges.push(GraphEdge {
-                                source: source_href.clone(),
-                                target: target_href,
-                                label: "link".to_string(),
-                            });
+                        let (href, default_label) = match heading {
+                            None => (entry.href.clone(), entry.title.clone()),
+                            Some(heading) => match entry.heading(heading) {
+                                Some(item) => (format!("{}#{}", entry.href, item.id), item.text.clone()),
+                                None => {
+                                    errors.push(format!(
+                                        "unresolved heading `{}` in reference `{}`",
+                                        heading,
+                                        whole.as_str()
+                                    ));
+                                    events.extend(broken_link(label.unwrap_or_else(|| heading.to_string()), whole.as_str()));
+                                    continue;
+                                }
+                            },
+                        };
+                        events.push(Event::Start(Tag::Link {
+                            link_type: LinkType::Inline,
+                            dest_url: href.into(),
+                            title: "".into(),
+                            id: "".into(),
+                        }));
+                        events.push(Event::Text(label.unwrap_or(default_label).into()));
+                        events.push(Event::End(TagEnd::Link));
+                    }
+                    if last < text.len() {
+                        events.push(Event::Text(text[last..].to_string().into()));
+                    }
+                    events
+                }
+                event => vec![event],
+            }
+        }
+
+        fn broken_link<'a>(label: String, reference: &str) -> Vec<Event<'a>> {
+            if !is_dev_mode() {
+                return vec![Event::Text(label.into())];
+            }
+            vec![
+                Event::Html(r#"<span class="itx-broken-link">"#.into()),
+                Event::Start(Tag::Link {
+                    link_type: LinkType::Inline,
+                    dest_url: "#".into(),
+                    title: format!("Unresolved reference: {}", reference).into(),
+                    id: "".into(),
+                }),
+                Event::Text(label.into()),
+                Event::End(TagEnd::Link),
+                Event::Html("</span>".into()),
+            ]
+        }
+
+        fn is_markdown_path(url: &str) -> bool {
+            let path = url.split('#').next().unwrap_or_default();
+            !path.contains("://") && path.ends_with(".md")
+        }
+
+        // Replaces `{{#include ...}}` lines with the file they point at, as a `[!code path]`
+        // panel. Lines inside fenced code blocks are left alone.
+        //
+        //   {{#include pe_parser/src/main.rs}}          -> the whole file
+        //   {{#include pe_parser/src/main.rs:10:40}}    -> lines 10 to 40, `:10:` and `::40` work too
+        //   {{#include pe_parser/src/main.rs:10}}       -> line 10 only
+        //   {{#include pe_parser/src/main.rs:sections}} -> between `ANCHOR: sections` and `ANCHOR_END: sections`
+        //
+        // Files or ranges that can't be read are pushed to `errors`; in dev mode they are
+        // rendered as a visible note, otherwise they are dropped.
+        fn include_snippets(markdown: &str, errors: &mut Vec<String>) -> String {
+            let include_re = regex::Regex::new(r"^\s*\{\{#include\s+([^\s:}]+)(?::([^\s}]*))?\s*\}\}\s*$").unwrap();
+            let mut output = String::with_capacity(markdown.len());
+            let mut fence: Option<&str> = None;
+            for line in markdown.lines() {
+                let trimmed = line.trim_start();
+                let marker = if trimmed.starts_with("```") { "```" } else if trimmed.starts_with("~~~") { "~~~" } else { "" };
+                match fence {
+                    Some(open) if marker == open => fence = None,
+                    None if !marker.is_empty() => fence = Some(marker),
+                    _ => {}
+                }
+                let Some(caps) = include_re.captures(line).filter(|_| fence.is_none()) else {
+                    output.push_str(line);
+                    output.push('\n');
+                    continue;
+                };
+                let path = &caps[1];
+                match read_snippet(path, caps.get(2).map_or("", |m| m.as_str())) {
+                    Ok(code) => {
+                        // A fence longer than any backtick run in the snippet, so it can't end early.
+                        let longest = code.split(|c| c != '`').map(str::len).max().unwrap_or(0);
+                        let fence = "`".repeat(longest.max(2) + 1);
+                        output.push_str(&format!(
+                            "\n> [!code {}]\n\n{}{}\n{}\n{}\n\n",
+                            path,
+                            fence,
+                            snippet_language(path),
+                            code,
+                            fence
+                        ));
+                    }
+                    Err(e) => {
+                        errors.push(format!("unable to include `{}`: {}", line.trim(), e));
+                        if is_dev_mode() {
+                            output.push_str(&format!("\n> **Missing snippet** `{}`: {}\n\n", path, e));
                         }
                     }
                 }
             }
+            output
         }
 
-        GraphData { nodes, edges }
-    }).await;
+        fn read_snippet(path: &str, range: &str) -> Result<String, String> {
+            let root = Path::new(&site_config().snippets_dir);
+            let file = root
+                .join(path)
+                .canonicalize()
+                .map_err(|e| e.to_string())?;
+            if !root.canonicalize().is_ok_and(|root| file.starts_with(root)) {
+                return Err("outside of the snippets directory".to_string());
+            }
+            let source = fs::read_to_string(&file).map_err(|e| e.to_string())?;
+            select_lines(&source, range)
+        }
 
-    match result {
-        Ok(data) => Ok(data),
-        Err(e) => Err(ServerFnError::new(format!("Threading error: {}", e))),
-    }
-}
-cfg_if::cfg_if! {
-    if #[cfg(feature = "ssr")] {
-        use std::{
-            fs::{self, DirEntry},
-            path::Path,
-        };
-        use chrono::NaiveDate;
+        // The lines of `source` in `range`, a line, a line range or an anchor name, dedented.
+        fn select_lines(source: &str, range: &str) -> Result<String, String> {
+            let lines: Vec<&str> = source.lines().collect();
+
+            let is_line = |part: &str| part.is_empty() || part.chars().all(|c| c.is_ascii_digit());
+            let selected: Vec<&str> = match range.split_once(':') {
+                _ if range.is_empty() => lines.clone(),
+                None if !is_line(range) => {
+                    let start = lines
+                        .iter()
+                        .position(|line| anchor(line, "ANCHOR") == Some(range))
+                        .ok_or_else(|| format!("no `ANCHOR: {}`", range))?;
+                    let end = lines[start..]
+                        .iter()
+                        .position(|line| anchor(line, "ANCHOR_END") == Some(range))
+                        .ok_or_else(|| format!("no `ANCHOR_END: {}`", range))?;
+                    lines[start + 1..start + end].to_vec()
+                }
+                split => {
+                    let (start, end) = split.unwrap_or((range, range));
+                    if !is_line(start) || !is_line(end) {
+                        return Err(format!("invalid range `{}`", range));
+                    }
+                    let start = start.parse::<usize>().unwrap_or(1).max(1);
+                    let end = end.parse::<usize>().unwrap_or(lines.len());
+                    if start > end || end > lines.len() {
+                        return Err(format!("lines {}-{} out of {}", start, end, lines.len()));
+                    }
+                    lines[start - 1..end].to_vec()
+                }
+            };
+
+            // Anchors of other snippets in the same file aren't part of the code.
+            let selected: Vec<&str> = selected
+                .into_iter()
+                .filter(|line| anchor(line, "ANCHOR").is_none() && anchor(line, "ANCHOR_END").is_none())
+                .collect();
+            let indent = selected
+                .iter()
+                .filter(|line| !line.trim().is_empty())
+                .map(|line| line.len() - line.trim_start().len())
+                .min()
+                .unwrap_or(0);
+            Ok(selected
+                .iter()
+                .map(|line| line.get(indent..).unwrap_or("").trim_end())
+                .collect::<Vec<_>>()
+                .join("\n"))
+        }
+
+        // The name in a `// ANCHOR: name` style comment, whatever the comment syntax.
+        fn anchor<'a>(line: &'a str, kind: &str) -> Option<&'a str> {
+            let (_, rest) = line.split_once(kind)?;
+            rest.strip_prefix(':').map(str::trim)
+        }
+
+        // highlight.js name of the language, from the file extension.
+        fn snippet_language(path: &str) -> &str {
+            match Path::new(path).extension().and_then(|ext| ext.to_str()).unwrap_or_default() {
+                "rs" => "rust",
+                "c" | "h" => "c",
+                "cpp" | "cc" | "hpp" => "cpp",
+                "py" => "python",
+                "js" => "javascript",
+                "ts" => "typescript",
+                "sh" => "bash",
+                "asm" | "s" => "x86asm",
+                "toml" => "toml",
+                "json" => "json",
+                "yml" | "yaml" => "yaml",
+                "go" => "go",
+                other => other,
+            }
+        }
 
         pub fn get_posts_file<P: AsRef<Path>>(path: P) -> Vec<DirEntry> {
             fs::read_dir(path)
@@ -232,10 +531,11 @@ cfg_if::cfg_if! {
             fs::read_to_string(entry.path()).ok()
         }
 
-        pub fn parse_post_content(content: &str) -> Option<Post> {
+        #[tracing::instrument(skip_all, fields(title = tracing::field::Empty))]
+        pub fn parse_post_content(content: &str, index: &PostIndex) -> Option<Post> {
             use gray_matter::engine::YAML;
             use gray_matter::Matter;
-            use pulldown_cmark::{html, Event, Tag, TagEnd, HeadingLevel, Options, Parser};
+            use pulldown_cmark::{html, HeadingLevel, Options, Parser, TextMergeStream};
 
             let mut options = Options::empty();
             options.insert(Options::ENABLE_HEADING_ATTRIBUTES);
@@ -244,9 +544,12 @@ cfg_if::cfg_if! {
             let post_data = matter
                 .parse_with_struct::<PostMetadata>(content)
                 .expect("Unable to parse md frontmatter");
-            let post_metadata = post_data.data;
+            let mut post_metadata = post_data.data;
+            post_metadata.cover = crate::images::responsive_image(&post_metadata.image_path);
+            tracing::Span::current().record("title", post_metadata.title.as_str());
 
-            let content = post_data.content;
+            let mut content_errors = Vec::new();
+            let content = include_snippets(&post_data.content, &mut content_errors);
 
             let parser = Parser::new_ext(&content, options);
 
@@ -255,27 +558,30 @@ cfg_if::cfg_if! {
             let mut events = Vec::new();
             let mut current_heading_level = None;
             let mut current_heading_text = String::new();
+            // Inline content of the heading, kept so code spans and emphasis survive.
+            let mut current_heading_events = Vec::new();
+            let mut in_code_block = false;
+
+            let parser = TextMergeStream::new(parser).flat_map(|event| {
+                resolve_post_links(event, &mut in_code_block, index, &mut content_errors)
+            });
 
             for event in parser {
                 match &event {
                     Event::Start(Tag::Heading { level, .. }) => {
                         current_heading_level = Some(*level);
                         current_heading_text.clear();
+                        current_heading_events.clear();
+                        continue;
                     }
-                    Event::Text(text) if current_heading_level.is_some() => {
+                    // Same text as `collect_headings`, so links to the heading find its id.
+                    Event::Text(text) | Event::Code(text) if current_heading_level.is_some() => {
                         current_heading_text.push_str(text);
                     }
                     Event::End(TagEnd::Heading(_level)) => {
                         if let Some(heading_level) = current_heading_level {
                             // Create ID from heading text
-                            let id = current_heading_text
-                                .to_lowercase()
-                                .chars()
-                                .map(|c| if c.is_alphanumeric() || c == ' ' { c } else { ' ' })
-                                .collect::<String>()
-                                .split_whitespace()
-                                .collect::<Vec<_>>()
-                                .join("-");
+                            let id = heading_id(&current_heading_text);
 
                             // Add to TOC (only h2)
                             let level_num = match heading_level {
@@ -297,7 +603,7 @@ cfg_if::cfg_if! {
 
                             // Replace heading with one that has an ID
                             events.push(Event::Html(format!(r#"<h{} id="{}">"#, level_num, id).into()));
-                            events.push(Event::Text(current_heading_text.clone().into()));
+                            events.append(&mut current_heading_events);
                             events.push(Event::Html(format!(r#"</h{}>"#, level_num).into()));
 
                             current_heading_level = None;
@@ -307,15 +613,62 @@ cfg_if::cfg_if! {
                     _ => {}
                 }
 
-                // Don't add heading events as we're replacing them
+                // Heading content is held back until the heading is replaced
                 if current_heading_level.is_none() {
                     events.push(event);
+                } else {
+                    current_heading_events.push(event);
                 }
             }
 
             let mut html_output = String::new();
             html::push_html(&mut html_output, events.into_iter());
 
+            // Post-process: an image alone in its paragraph becomes a figure, captioned with its
+            // title. An attribute block after it sets the width and alignment:
+            //
+            //   ![Stack layout](/images/blog_images/stack.png "The stack before the overflow"){width=60% align=right}
+            let figure_re = regex::Regex::new(r#"<p>(<img [^>]*?/>)(?:\{([^}]*)\})?</p>"#).unwrap();
+            let title_re = regex::Regex::new(r#"\s+title="([^"]*)""#).unwrap();
+            html_output = figure_re.replace_all(&html_output, |caps: &regex::Captures| {
+                let image = &caps[1];
+                let caption = title_re.captures(image).map(|title| title[1].to_string());
+                if caption.is_none() && caps.get(2).is_none() {
+                    return caps[0].to_string();
+                }
+                let (class, style) = figure_attributes(caps.get(2).map_or("", |m| m.as_str()));
+                format!(
+                    r#"<figure class="itx-figure{}"{}>{}{}</figure>"#,
+                    class,
+                    style,
+                    title_re.replace(image, ""),
+                    caption.map(|caption| format!("<figcaption>{}</figcaption>", caption)).unwrap_or_default()
+                )
+            }).to_string();
+            // An image inside text only takes the width from its block.
+            let inline_image_re = regex::Regex::new(r#"<img ([^>]*?)\s*/>\{([^}]*)\}"#).unwrap();
+            html_output = inline_image_re.replace_all(&html_output, |caps: &regex::Captures| {
+                let (_, style) = figure_attributes(&caps[2]);
+                format!("<img {}{} />", &caps[1], style)
+            }).to_string();
+
+            // Post-process: images, from markdown or inline html, get their resized copies and
+            // are loaded lazily. Images that set their own size are left alone.
+            let image_re = regex::Regex::new(r#"<img\s+([^>]*?)\s*/?>"#).unwrap();
+            let src_re = regex::Regex::new(r#"(?:^|\s)src="([^"]*)""#).unwrap();
+            html_output = image_re.replace_all(&html_output, |caps: &regex::Captures| {
+                let attributes = &caps[1];
+                let Some(src) = src_re.captures(attributes) else { return caps[0].to_string() };
+                if attributes.contains("width=") || attributes.contains("srcset=") {
+                    return caps[0].to_string();
+                }
+                let others = src_re.replace(attributes, "").trim().to_string();
+                match crate::images::responsive_image(&src[1]) {
+                    Some(image) => image.to_html(&others, POST_IMAGE_SIZES),
+                    None => format!(r#"<img {} loading="lazy" decoding="async" />"#, attributes),
+                }
+            }).to_string();
+
             // Post-process: transform [!author] blockquotes into styled callouts
             let author_re = regex::Regex::new(
                 r"(?s)<blockquote>\s*<p>\[!author\]\s*(.*?)</p>\s*</blockquote>"
@@ -323,11 +676,49 @@ cfg_if::cfg_if! {
             html_output = author_re.replace_all(&html_output, |caps: &regex::Captures| {
                 let text = &caps[1];
                 format!(
-                    r#"<div class="flex flex-col sm:flex-row gap-4 items-center sm:items-start p-4 my-6 rounded-lg bg-[#161B22] border border-[#30363D] text-center sm:text-left"><img src="https://github.com/itehax.png" class="w-10 h-10 rounded-full shrink-0" alt="Author" /><div class="text-[#C9D1D9] text-sm leading-relaxed"><p>{}</p></div></div>"#,
+                    r#"<div class="flex flex-col sm:flex-row gap-4 items-center sm:items-start p-4 my-6 rounded-lg bg-[#161B22] border border-[#30363D] text-center sm:text-left"><img src="{}" class="w-10 h-10 rounded-full shrink-0" alt="Author" data-no-zoom /><div class="text-[#C9D1D9] text-sm leading-relaxed"><p>{}</p></div></div>"#,
+                    site_config().author.avatar_url,
                     text.trim()
                 )
             }).to_string();
 
+            // Post-process: ```dot and ```bytefield blocks → inline svg diagrams, with their
+            // source kept underneath. Blocks that fail to render stay as code.
+            let diagram_re = regex::Regex::new(
+                r#"<pre><code class="language-(dot|bytefield)">([\s\S]*?)</code></pre>"#
+            ).unwrap();
+            let mut diagrams = 0;
+            html_output = diagram_re.replace_all(&html_output, |caps: &regex::Captures| {
+                let source = strip_html(&caps[2]);
+                diagrams += 1;
+                match crate::diagrams::render_diagram(&caps[1], &source, diagrams) {
+                    Some(Ok(svg)) => format!(
+                        r#"<figure class="itx-diagram">{}<details><summary>Source</summary>{}</details></figure>"#,
+                        svg, &caps[0]
+                    ),
+                    Some(Err(e)) => {
+                        content_errors.push(format!("unable to render {} diagram: {}", &caps[1], e));
+                        caps[0].to_string()
+                    }
+                    None => caps[0].to_string(),
+                }
+            }).to_string();
+
+            // Post-process: ```hexdump blocks → offset / hex / ascii table with the annotated
+            // ranges highlighted. Blocks that fail to parse stay as code.
+            let hexdump_re = regex::Regex::new(
+                r#"<pre><code class="language-hexdump">([\s\S]*?)</code></pre>"#
+            ).unwrap();
+            html_output = hexdump_re.replace_all(&html_output, |caps: &regex::Captures| {
+                match crate::hexdump::render_hexdump(&strip_html(&caps[1])) {
+                    Ok(table) => table,
+                    Err(e) => {
+                        content_errors.push(format!("unable to render hexdump: {}", e));
+                        caps[0].to_string()
+                    }
+                }
+            }).to_string();
+
             // Post-process: [!code Title] + fenced code block → styled code panel
             //
             //   > [!code src/main.rs]
@@ -380,33 +771,234 @@ cfg_if::cfg_if! {
                 )
             }).to_string();
 
-            Some(Post::new(post_metadata, html_output, toc))
+            // Post-process: [!cast file.cast Title] → terminal recording, played in the page and
+            // shown as its final transcript where there is no player, e.g. in the feed.
+            //
+            //   > [!cast rop-chain.cast Building the ROP chain]
+            //
+            // Recordings live in `public/casts`; a path starting with `/` is taken as is. The
+            // title defaults to the file name.
+            let cast_re = regex::Regex::new(
+                r#"(?s)<blockquote>\s*<p>\[!cast\s+([^\]\s]+)(?:\s+([^\]]*))?\]</p>\s*</blockquote>"#
+            ).unwrap();
+            html_output = cast_re.replace_all(&html_output, |caps: &regex::Captures| {
+                let src = match &caps[1] {
+                    src if src.starts_with('/') => src.to_string(),
+                    name => format!("/casts/{}", name),
+                };
+                let cast = match crate::server_functions::cast::read_cast(&src) {
+                    Ok(cast) => cast,
+                    Err(e) => {
+                        content_errors.push(format!("unable to embed recording `{}`: {}", src, e));
+                        return match is_dev_mode() {
+                            true => format!("<blockquote><p><strong>Missing recording</strong> <code>{}</code>: {}</p></blockquote>", src, e),
+                            false => String::new(),
+                        };
+                    }
+                };
+                let mut terminal = Terminal::new(cast.width, cast.height);
+                for (_, output) in &cast.events {
+                    terminal.write(output);
+                }
+                let title = caps.get(2)
+                    .map(|m| m.as_str().trim().to_string())
+                    .filter(|title| !title.is_empty())
+                    .unwrap_or_else(|| src.rsplit('/').next().unwrap_or_default().to_string());
+                cast_html(&CastMeta { src, title }, &terminal.transcript())
+            }).to_string();
+
+            let mut post = Post::new(post_metadata, html_output, toc);
+            post.content_errors = content_errors;
+            Some(post)
         }
 
-        pub fn sort_posts(posts: &mut [Post]) {
-            posts.sort_by(|a, b| {
-                let a_date = NaiveDate::parse_from_str(&a.post_metadata.date, "%Y-%m-%d").unwrap();
-                let b_date = NaiveDate::parse_from_str(&b.post_metadata.date, "%Y-%m-%d").unwrap();
-                b_date.cmp(&a_date)
-            });
+        // Reads and renders every post. Callers go through `crate::content::content`, which
+        // only does so when a file changed.
+        pub fn load_posts(index: &PostIndex) -> Posts {
+            let mut all_posts = BTreeMap::new();
+
+            for collection in &site_config().collections {
+                let mut posts = if Path::new(&collection.dir).exists() {
+                    process_posts(&collection.dir, index)
+                } else {
+                    leptos::logging::warn!("collection {} has no directory {}", collection.name, collection.dir);
+                    Vec::new()
+                };
+                sort_posts(&mut posts, collection.sort);
+                all_posts.insert(collection.name.clone(), posts);
+            }
+            all_posts
+        }
+
+        // Class suffix and style of a figure from its `{width=60% align=right}` block. Anything
+        // else in the block is ignored.
+        fn figure_attributes(block: &str) -> (String, String) {
+            let width_re = regex::Regex::new(r"^\d+(\.\d+)?(%|px|rem)$").unwrap();
+            let mut class = String::new();
+            let mut style = String::new();
+            for (key, value) in block.split_whitespace().filter_map(|pair| pair.split_once('=')) {
+                let value = value.trim_matches('"');
+                match key {
+                    "width" if width_re.is_match(value) => style = format!(r#" style="width: {}""#, value),
+                    "align" if matches!(value, "left" | "right" | "center") => {
+                        class = format!(" itx-figure-{}", value)
+                    }
+                    _ => {}
+                }
+            }
+            (class, style)
         }
 
-        pub fn process_posts<P: AsRef<Path>>(path: P) -> Vec<Post> {
+        // Returns the `/<collection>/<slug>` target of every internal link in the rendered html.
+        pub fn internal_links(content: &str) -> Vec<String> {
+            let link_re = regex::Regex::new(r##"href="(/[^"#]+/[^"#/]+)(?:#[^"]*)?""##).unwrap();
+            let config = site_config();
+            link_re
+                .captures_iter(content)
+                .map(|cap| cap[1].to_string())
+                .filter(|href| config.collection_for_href(href).is_some())
+                .collect()
+        }
+
+        pub fn sort_posts(posts: &mut [Post], order: SortOrder) {
+            let date = |post: &Post| NaiveDate::parse_from_str(&post.post_metadata.date, "%Y-%m-%d").unwrap();
+            match order {
+                SortOrder::Newest => posts.sort_by_key(|post| std::cmp::Reverse(date(post))),
+                SortOrder::Oldest => posts.sort_by_key(date),
+                SortOrder::Title => posts.sort_by(|a, b| a.post_metadata.title.cmp(&b.post_metadata.title)),
+            }
+        }
+
+        // Plain text of rendered html, used for similarity scoring and link context.
+        pub fn strip_html(html: &str) -> String {
+            let tag_re = regex::Regex::new(r"<[^>]*>").unwrap();
+            tag_re
+                .replace_all(html, "")
+                .replace("&quot;", "\"")
+                .replace("&#39;", "'")
+                .replace("&lt;", "<")
+                .replace("&gt;", ">")
+                .replace("&amp;", "&")
+        }
+
+        pub fn process_posts<P: AsRef<Path>>(path: P, index: &PostIndex) -> Vec<Post> {
             let posts_text = get_posts_file(path);
             let mut posts = Vec::new();
 
             for entry in posts_text {
+                let file = entry.path();
                 if let Some(content) = read_post_content(entry) {
-                    if let Some(post) = parse_post_content(&content) {
+                    if let Some(post) = parse_post_content(&content, index).filter(|post| !post.post_metadata.removed) {
+                        for error in &post.content_errors {
+                            leptos::logging::warn!("content error in {}: {}", file.display(), error);
+                        }
                         posts.push(post);
                     }
                 }
             }
 
-            sort_posts(&mut posts);
-
             posts
         }
 
+        #[cfg(test)]
+        mod tests {
+            use super::*;
+
+            fn index() -> PostIndex {
+                PostIndex {
+                    entries: vec![IndexEntry {
+                        href: "/blog/set-2-challenges".to_string(),
+                        title: "Set 2 challenges".to_string(),
+                        file: "blog/set2.md".to_string(),
+                        headings: collect_headings("# Intro\n\n## Challenge 12!\n\n## The `Vec` type\n"),
+                    }],
+                    tombstones: Vec::new(),
+                }
+            }
+
+            fn resolve(text: &str) -> (Vec<Event<'_>>, Vec<String>) {
+                let mut errors = Vec::new();
+                let events = resolve_post_links(Event::Text(text.into()), &mut false, &index(), &mut errors);
+                (events, errors)
+            }
+
+            const SNIPPET: &str = "use std::fs;\n\n// ANCHOR: main\nfn main() {\n    // ANCHOR: body\n    let data = fs::read(\"a.exe\");\n    // ANCHOR_END: body\n}\n// ANCHOR_END: main\n";
+
+            fn link_href(events: &[Event]) -> Option<String> {
+                events.iter().find_map(|event| match event {
+                    Event::Start(Tag::Link { dest_url, .. }) => Some(dest_url.to_string()),
+                    _ => None,
+                })
+            }
+
+            #[test]
+            fn heading_ids() {
+                assert_eq!(heading_id("Challenge 12!"), "challenge-12");
+                assert_eq!(heading_id("  What's   `new`?  "), "what-s-new");
+            }
+
+            #[test]
+            fn headings_include_inline_code() {
+                let headings = index().entries[0].headings.clone();
+                let ids: Vec<_> = headings.iter().map(|item| item.id.as_str()).collect();
+                assert_eq!(ids, ["intro", "challenge-12", "the-vec-type"]);
+                assert_eq!(headings[2].text, "The Vec type");
+                assert_eq!(headings[2].level, 2);
+            }
+
+            #[test]
+            fn slugs_resolve_by_file_stem_or_title() {
+                let index = index();
+                assert_eq!(index.resolve_slug("set2").unwrap().file, "blog/set2.md");
+                assert_eq!(index.resolve_slug(" Set-2-Challenges ").unwrap().file, "blog/set2.md");
+                assert!(index.resolve_slug("set").is_none());
+            }
+
+            #[test]
+            fn paths_resolve_relative_to_any_collection() {
+                let index = index();
+                assert!(index.resolve_path("set2.md").is_some());
+                assert!(index.resolve_path("../blog/set2.md").is_some());
+                assert!(index.resolve_path("./notes/set2.md").is_none());
+            }
+
+            #[test]
+            fn wiki_links_resolve_to_posts_and_headings() {
+                let (events, errors) = resolve("see [[set2]] first");
+                assert!(errors.is_empty());
+                assert_eq!(link_href(&events).as_deref(), Some("/blog/set-2-challenges"));
+                assert!(events.contains(&Event::Text("Set 2 challenges".into())));
+
+                let (events, errors) = resolve("[[set2#the vec type|the type]]");
+                assert!(errors.is_empty());
+                assert_eq!(link_href(&events).as_deref(), Some("/blog/set-2-challenges#the-vec-type"));
+                assert!(events.contains(&Event::Text("the type".into())));
+            }
+
+            #[test]
+            fn snippet_line_ranges() {
+                assert_eq!(select_lines(SNIPPET, "1").unwrap(), "use std::fs;");
+                assert_eq!(select_lines(SNIPPET, "4:").unwrap(), "fn main() {\n    let data = fs::read(\"a.exe\");\n}");
+                assert_eq!(select_lines(SNIPPET, ":1").unwrap(), "use std::fs;");
+                assert!(select_lines(SNIPPET, "8:4").is_err());
+                assert!(select_lines(SNIPPET, "10").unwrap_err().contains("out of 9"));
+                assert!(select_lines(SNIPPET, "1:x").is_err());
+            }
+
+            #[test]
+            fn snippet_anchors_are_dedented_and_nest() {
+                assert_eq!(select_lines(SNIPPET, "body").unwrap(), "let data = fs::read(\"a.exe\");");
+                assert_eq!(select_lines(SNIPPET, "main").unwrap(), "fn main() {\n    let data = fs::read(\"a.exe\");\n}");
+                assert_eq!(select_lines(SNIPPET, "missing").unwrap_err(), "no `ANCHOR: missing`");
+            }
+
+            #[test]
+            fn unresolved_wiki_links_are_reported() {
+                let (_, errors) = resolve("[[missing]] and [[set2#nowhere]]");
+                assert_eq!(errors.len(), 2);
+                assert!(errors[0].contains("[[missing]]"));
+                assert!(errors[1].contains("unresolved heading `nowhere`"));
+            }
+        }
     }
 }
@@ -0,0 +1,225 @@
+use crate::server_functions::posts::PostMetadata;
+use leptos::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RelatedPost {
+    pub collection: String,
+    pub href: String,
+    pub post_metadata: PostMetadata,
+    pub score: f64,
+}
+
+#[server(GetRelatedPosts, "/api")]
+#[cfg_attr(feature = "ssr", tracing::instrument(skip_all))]
+pub async fn get_related_posts(post_href: String) -> Result<Vec<RelatedPost>, ServerFnError> {
+    let result = tokio::task::spawn_blocking(move || {
+        crate::content::content().related.related_posts(&post_href, MAX_RELATED_POSTS)
+    })
+    .await;
+
+    match result {
+        Ok(related) => Ok(related),
+        Err(e) => Err(ServerFnError::new(format!("Threading error: {}", e))),
+    }
+}
+
+cfg_if::cfg_if! {
+    if #[cfg(feature = "ssr")] {
+        use crate::config::site_config;
+        use crate::server_functions::posts::{internal_links, Post, Posts};
+        use std::collections::{HashMap, HashSet};
+
+        const MAX_RELATED_POSTS: usize = 3;
+
+        // Relative weight of each signal in the final score.
+        const TAG_WEIGHT: f64 = 1.0;
+        const LINK_WEIGHT: f64 = 2.0;
+        const SERIES_WEIGHT: f64 = 3.0;
+        const SIMILARITY_WEIGHT: f64 = 4.0;
+
+        const STOP_WORDS: &[&str] = &[
+            "the", "and", "for", "that", "this", "with", "you", "are", "not", "but", "can",
+            "have", "from", "was", "will", "all", "its", "our", "your", "they", "their", "what",
+            "which", "when", "there", "then", "than", "into", "also", "just", "about", "some",
+            "how", "use", "using", "used", "one", "two", "more", "out", "now", "get", "let",
+        ];
+
+        struct Candidate {
+            collection: String,
+            href: String,
+            post_metadata: PostMetadata,
+            links: HashSet<String>,
+            // Tf-idf weight of every term of the post body.
+            vector: HashMap<String, f64>,
+        }
+
+        // Every searchable post with the signals it is scored on. `crate::content` builds it
+        // once per change to the posts.
+        pub struct RelatedModel {
+            candidates: Vec<Candidate>,
+        }
+
+        impl RelatedModel {
+            pub fn build(posts: &Posts) -> Self {
+                let posts: Vec<(&str, String, &Post)> = site_config()
+                    .collections
+                    .iter()
+                    .filter(|collection| collection.in_search)
+                    .flat_map(|collection| {
+                        posts.get(&collection.name).into_iter().flatten().map(move |post| {
+                            (collection.name.as_str(), collection.href(&post.post_metadata.create_href()), post)
+                        })
+                    })
+                    .collect();
+                let vectors = tf_idf(
+                    &posts
+                        .iter()
+                        .map(|(_, _, post)| tokenize(&post.post_content))
+                        .collect::<Vec<_>>(),
+                );
+
+                let candidates = posts
+                    .into_iter()
+                    .zip(vectors)
+                    .map(|((collection, href, post), vector)| Candidate {
+                        collection: collection.to_string(),
+                        href,
+                        post_metadata: post.post_metadata.clone(),
+                        links: internal_links(&post.post_content).into_iter().collect(),
+                        vector,
+                    })
+                    .collect();
+                Self { candidates }
+            }
+
+            // Scores every other searchable post against `post_href` and returns the best `limit`.
+            pub fn related_posts(&self, post_href: &str, limit: usize) -> Vec<RelatedPost> {
+                let Some(source) = self.candidates.iter().find(|c| c.href == post_href) else {
+                    return Vec::new();
+                };
+
+                let mut related: Vec<RelatedPost> = self
+                    .candidates
+                    .iter()
+                    .filter(|candidate| candidate.href != source.href)
+                    .filter_map(|candidate| {
+                        let shared_tags = source
+                            .post_metadata
+                            .tags
+                            .iter()
+                            .filter(|tag| candidate.post_metadata.tags.contains(tag))
+                            .count();
+                        let linked = source.links.contains(&candidate.href)
+                            || candidate.links.contains(&source.href);
+                        let same_series = source.post_metadata.series.is_some()
+                            && source.post_metadata.series == candidate.post_metadata.series;
+                        let similarity = cosine_similarity(&source.vector, &candidate.vector);
+
+                        let score = TAG_WEIGHT * shared_tags as f64
+                            + if linked { LINK_WEIGHT } else { 0.0 }
+                            + if same_series { SERIES_WEIGHT } else { 0.0 }
+                            + SIMILARITY_WEIGHT * similarity;
+
+                        (score > 0.0).then(|| RelatedPost {
+                            collection: candidate.collection.clone(),
+                            href: candidate.href.clone(),
+                            post_metadata: candidate.post_metadata.clone(),
+                            score,
+                        })
+                    })
+                    .collect();
+
+                related.sort_by(|a, b| b.score.total_cmp(&a.score));
+                related.truncate(limit);
+                related
+            }
+        }
+
+        // Lowercased words of the post body, with html tags, short words and stop words removed.
+        fn tokenize(html: &str) -> Vec<String> {
+            let tag_re = regex::Regex::new(r"<[^>]*>").unwrap();
+            let text = tag_re.replace_all(html, " ").to_lowercase();
+            text.split(|c: char| !c.is_alphanumeric())
+                .filter(|word| word.len() > 2 && !STOP_WORDS.contains(word))
+                .map(str::to_string)
+                .collect()
+        }
+
+        fn tf_idf(documents: &[Vec<String>]) -> Vec<HashMap<String, f64>> {
+            let mut document_frequency: HashMap<&str, usize> = HashMap::new();
+            for document in documents {
+                let unique: HashSet<&str> = document.iter().map(String::as_str).collect();
+                for term in unique {
+                    *document_frequency.entry(term).or_default() += 1;
+                }
+            }
+
+            let total = documents.len() as f64;
+            documents
+                .iter()
+                .map(|document| {
+                    let mut term_frequency: HashMap<String, f64> = HashMap::new();
+                    for term in document {
+                        *term_frequency.entry(term.clone()).or_default() += 1.0;
+                    }
+                    let len = document.len().max(1) as f64;
+                    term_frequency
+                        .into_iter()
+                        .map(|(term, count)| {
+                            let idf = (total / document_frequency[term.as_str()] as f64).ln();
+                            (term, count / len * idf)
+                        })
+                        .collect()
+                })
+                .collect()
+        }
+
+        fn cosine_similarity(a: &HashMap<String, f64>, b: &HashMap<String, f64>) -> f64 {
+            let dot: f64 = a
+                .iter()
+                .filter_map(|(term, weight)| b.get(term).map(|other| weight * other))
+                .sum();
+            let norm_a = a.values().map(|w| w * w).sum::<f64>().sqrt();
+            let norm_b = b.values().map(|w| w * w).sum::<f64>().sqrt();
+            if norm_a == 0.0 || norm_b == 0.0 {
+                0.0
+            } else {
+                dot / (norm_a * norm_b)
+            }
+        }
+
+        #[cfg(test)]
+        mod tests {
+            use super::*;
+
+            fn words(text: &str) -> Vec<String> {
+                text.split_whitespace().map(str::to_string).collect()
+            }
+
+            #[test]
+            fn tokenize_drops_tags_short_and_stop_words() {
+                assert_eq!(tokenize("<p>The <code>Heap</code> is on it, for sure</p>"), ["heap", "sure"]);
+            }
+
+            #[test]
+            fn terms_in_every_document_weigh_nothing() {
+                let vectors = tf_idf(&[words("heap common"), words("stack common")]);
+                assert_eq!(vectors[0]["common"], 0.0);
+                assert!(vectors[0]["heap"] > 0.0);
+                assert!(!vectors[0].contains_key("stack"));
+            }
+
+            #[test]
+            fn cosine_similarity_is_bounded() {
+                let vectors = tf_idf(&[words("heap heap tcache"), words("heap tcache tcache"), words("kernel driver"), words("")]);
+                let same = cosine_similarity(&vectors[0], &vectors[0]);
+                assert!((same - 1.0).abs() < 1e-9);
+                let close = cosine_similarity(&vectors[0], &vectors[1]);
+                assert!(close > 0.0 && close < 1.0);
+                assert_eq!(cosine_similarity(&vectors[0], &vectors[2]), 0.0);
+                assert_eq!(cosine_similarity(&vectors[0], &vectors[3]), 0.0);
+            }
+        }
+    }
+}
@@ -0,0 +1,167 @@
+use leptos::*;
+use serde::{Deserialize, Serialize};
+
+// Days covered by `/admin/stats`, ending today.
+pub const STATS_DAYS: usize = 30;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct SiteStats {
+    // `%Y-%m-%d`, oldest first. Every other per-day list follows this order.
+    pub days: Vec<String>,
+    pub views: Vec<u64>,
+    pub visitors: Vec<u64>,
+    // Estimated feed readers per day.
+    pub subscribers: Vec<u64>,
+    // Posts by views over the whole period, most viewed first.
+    pub posts: Vec<PostStats>,
+    pub referrers: Vec<(String, u64)>,
+    pub countries: Vec<(String, u64)>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PostStats {
+    pub href: String,
+    pub title: String,
+    pub total: u64,
+    pub views: Vec<u64>,
+}
+
+#[server(GetSiteStats, "/api")]
+#[cfg_attr(feature = "ssr", tracing::instrument(skip_all))]
+pub async fn get_site_stats() -> Result<SiteStats, ServerFnError> {
+    let authorized = use_context::<http::request::Parts>()
+        .is_some_and(|parts| crate::analytics::is_admin(&parts.headers));
+    if !authorized {
+        return Err(ServerFnError::new(crate::error_template::AppError::Unauthorized));
+    }
+
+    let result = tokio::task::spawn_blocking(site_stats).await;
+    match result {
+        Ok(stats) => Ok(stats),
+        Err(e) => Err(ServerFnError::new(format!("Threading error: {}", e))),
+    }
+}
+
+cfg_if::cfg_if! {
+    if #[cfg(feature = "ssr")] {
+        use crate::analytics::{ViewEvent, ViewKind};
+        use crate::config::site_config;
+        use crate::content::content;
+        use chrono::{Duration, NaiveDate, Utc};
+        use std::collections::{BTreeMap, HashMap, HashSet};
+        use std::io::{BufRead, BufReader};
+
+        const TOP_REFERRERS: usize = 20;
+        const TOP_COUNTRIES: usize = 20;
+
+        // Aggregates the analytics file. It is read from the start on every call, which is
+        // fine for the traffic of a personal site.
+        pub fn site_stats() -> SiteStats {
+            let today = Utc::now().date_naive();
+            let first_day = today - Duration::days(STATS_DAYS as i64 - 1);
+            let days: Vec<NaiveDate> = first_day.iter_days().take(STATS_DAYS).collect();
+            let day_index = |day: NaiveDate| (day >= first_day && day <= today).then(|| (day - first_day).num_days() as usize);
+
+            let mut views = vec![0; STATS_DAYS];
+            let mut visitors = vec![HashSet::new(); STATS_DAYS];
+            let mut post_views: HashMap<String, Vec<u64>> = HashMap::new();
+            let mut referrers: HashMap<String, u64> = HashMap::new();
+            let mut countries: HashMap<String, u64> = HashMap::new();
+            // Per day: the largest count each aggregator reported, and other distinct fetchers.
+            let mut aggregators = vec![HashMap::<String, u64>::new(); STATS_DAYS];
+            let mut feed_readers = vec![HashSet::new(); STATS_DAYS];
+
+            let path = &site_config().analytics.path;
+            let file = match std::fs::File::open(path) {
+                Ok(file) => file,
+                Err(e) => {
+                    if e.kind() != std::io::ErrorKind::NotFound {
+                        tracing::warn!("unable to read {}: {}", path, e);
+                    }
+                    return empty_stats(&days);
+                }
+            };
+            let events = BufReader::new(file)
+                .lines()
+                .map_while(Result::ok)
+                .filter_map(|line| serde_json::from_str::<ViewEvent>(&line).ok());
+
+            for event in events {
+                let Some(index) = day_index(event.time.date_naive()) else { continue };
+                match event.kind {
+                    ViewKind::Page => {
+                        views[index] += 1;
+                        visitors[index].insert(event.visitor);
+                        if site_config().collection_for_href(&event.path).is_some() {
+                            post_views.entry(event.path).or_insert_with(|| vec![0; STATS_DAYS])[index] += 1;
+                        }
+                        if let Some(referrer) = event.referrer {
+                            *referrers.entry(referrer).or_default() += 1;
+                        }
+                        if let Some(country) = event.country {
+                            *countries.entry(country).or_default() += 1;
+                        }
+                    }
+                    ViewKind::Feed => match (event.agent, event.subscribers) {
+                        (Some(agent), Some(subscribers)) => {
+                            let reported = aggregators[index].entry(agent).or_default();
+                            *reported = (*reported).max(subscribers);
+                        }
+                        _ => {
+                            feed_readers[index].insert(event.visitor);
+                        }
+                    },
+                }
+            }
+
+            let titles: HashMap<String, String> = content()
+                .index
+                .entries
+                .iter()
+                .map(|entry| (entry.href.clone(), entry.title.clone()))
+                .collect();
+            let mut posts: Vec<PostStats> = post_views
+                .into_iter()
+                .map(|(href, views)| PostStats {
+                    title: titles.get(&href).cloned().unwrap_or_else(|| href.clone()),
+                    total: views.iter().sum(),
+                    href,
+                    views,
+                })
+                .collect();
+            posts.sort_by(|a, b| b.total.cmp(&a.total).then_with(|| a.href.cmp(&b.href)));
+
+            SiteStats {
+                days: days.iter().map(|day| day.format("%Y-%m-%d").to_string()).collect(),
+                views,
+                visitors: visitors.iter().map(|day| day.len() as u64).collect(),
+                subscribers: aggregators
+                    .iter()
+                    .zip(&feed_readers)
+                    .map(|(aggregators, readers)| aggregators.values().sum::<u64>() + readers.len() as u64)
+                    .collect(),
+                posts,
+                referrers: top(referrers, TOP_REFERRERS),
+                countries: top(countries, TOP_COUNTRIES),
+            }
+        }
+
+        fn empty_stats(days: &[NaiveDate]) -> SiteStats {
+            SiteStats {
+                days: days.iter().map(|day| day.format("%Y-%m-%d").to_string()).collect(),
+                views: vec![0; days.len()],
+                visitors: vec![0; days.len()],
+                subscribers: vec![0; days.len()],
+                ..Default::default()
+            }
+        }
+
+        fn top(counts: HashMap<String, u64>, limit: usize) -> Vec<(String, u64)> {
+            // Sorted by name first so ties come out in a stable order.
+            let mut counts: Vec<(String, u64)> = counts.into_iter().collect::<BTreeMap<_, _>>().into_iter().collect();
+            counts.sort_by(|a, b| b.1.cmp(&a.1));
+            counts.truncate(limit);
+            counts
+        }
+    }
+}
@@ -0,0 +1,22 @@
+use crate::config::SiteConfig;
+use axum::extract::FromRef;
+use leptos::LeptosOptions;
+
+// Shared state of the axum router. Handlers can extract either part on its own.
+#[derive(Clone, Debug)]
+pub struct AppState {
+    pub leptos_options: LeptosOptions,
+    pub site_config: SiteConfig,
+}
+
+impl FromRef<AppState> for LeptosOptions {
+    fn from_ref(state: &AppState) -> Self {
+        state.leptos_options.clone()
+    }
+}
+
+impl FromRef<AppState> for SiteConfig {
+    fn from_ref(state: &AppState) -> Self {
+        state.site_config.clone()
+    }
+}
@@ -0,0 +1,175 @@
+use crate::config::{is_dev_mode, site_config};
+use crate::proxy::ClientInfo;
+use axum::{
+    extract::{MatchedPath, Request},
+    http::{
+        header::{CACHE_CONTROL, CONTENT_TYPE},
+        HeaderValue, Method, StatusCode,
+    },
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use prometheus::{
+    Encoder, HistogramOpts, HistogramVec, IntCounter, IntCounterVec, Opts, Registry, TextEncoder,
+};
+use std::{sync::OnceLock, time::Instant};
+use tracing::{field::Empty, Instrument};
+use tracing_subscriber::{fmt, layer::SubscriberExt, util::SubscriberInitExt, EnvFilter};
+
+pub const METRICS_PATH: &str = "/metrics";
+
+static METRICS: OnceLock<Metrics> = OnceLock::new();
+
+// Sets up logging for the whole server, `log` records included. `RUST_LOG` picks the levels
+// and `LOG_FORMAT` (`json` or `pretty`) the output; production defaults to json.
+pub fn init_tracing() {
+    let filter = EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info"));
+    let json = match std::env::var("LOG_FORMAT") {
+        Ok(format) => format.eq_ignore_ascii_case("json"),
+        Err(_) => !is_dev_mode(),
+    };
+    let registry = tracing_subscriber::registry().with(filter);
+    if json {
+        registry.with(fmt::layer().json().with_current_span(true).with_span_list(false)).init();
+    } else {
+        registry.with(fmt::layer().pretty()).init();
+    }
+}
+
+// Prometheus metrics exposed at `/metrics`, to the admin only.
+pub struct Metrics {
+    registry: Registry,
+    requests: IntCounterVec,
+    request_duration: HistogramVec,
+    post_views: IntCounterVec,
+    content_reloads: IntCounter,
+}
+
+impl Metrics {
+    fn new() -> Self {
+        let requests = IntCounterVec::new(
+            Opts::new("http_requests_total", "Requests handled, by route and status."),
+            &["method", "route", "status"],
+        )
+        .expect("valid metric");
+        let request_duration = HistogramVec::new(
+            HistogramOpts::new("http_request_duration_seconds", "Time spent handling a request."),
+            &["method", "route"],
+        )
+        .expect("valid metric");
+        let post_views = IntCounterVec::new(
+            Opts::new("post_views_total", "Successful page views of every post."),
+            &["collection", "post"],
+        )
+        .expect("valid metric");
+        let content_reloads = IntCounter::new(
+            "content_reloads_total",
+            "Times the posts were read and parsed from disk.",
+        )
+        .expect("valid metric");
+
+        let registry = Registry::new();
+        registry.register(Box::new(requests.clone())).expect("unique metric");
+        registry.register(Box::new(request_duration.clone())).expect("unique metric");
+        registry.register(Box::new(post_views.clone())).expect("unique metric");
+        registry.register(Box::new(content_reloads.clone())).expect("unique metric");
+
+        Self {
+            registry,
+            requests,
+            request_duration,
+            post_views,
+            content_reloads,
+        }
+    }
+
+    pub fn record_content_reload(&self) {
+        self.content_reloads.inc();
+    }
+
+    fn encode(&self) -> Result<String, prometheus::Error> {
+        let mut buffer = Vec::new();
+        TextEncoder::new().encode(&self.registry.gather(), &mut buffer)?;
+        Ok(String::from_utf8_lossy(&buffer).into_owned())
+    }
+}
+
+pub fn metrics() -> &'static Metrics {
+    METRICS.get_or_init(Metrics::new)
+}
+
+// Wraps every request in a span with its method, path and matched route, logs the status and
+// latency when it completes and updates the request metrics.
+pub async fn observe_requests(req: Request, next: Next) -> Response {
+    let method = req.method().clone();
+    let path = req.uri().path().to_string();
+    // Unmatched requests (static files and 404s) share a label, so paths can't add new series.
+    let route = req
+        .extensions()
+        .get::<MatchedPath>()
+        .map_or("fallback", |matched| matched.as_str())
+        .to_string();
+
+    let client_ip = req
+        .extensions()
+        .get::<ClientInfo>()
+        .and_then(|client| client.ip)
+        .map(|ip| ip.to_string())
+        .unwrap_or_default();
+
+    let span = tracing::info_span!(
+        "request",
+        method = %method,
+        path = %path,
+        route = %route,
+        client_ip = %client_ip,
+        status = Empty,
+        latency_ms = Empty,
+    );
+    let start = Instant::now();
+    let response = next.run(req).instrument(span.clone()).await;
+    let latency = start.elapsed();
+    let status = response.status();
+
+    span.record("status", status.as_u16());
+    span.record("latency_ms", latency.as_secs_f64() * 1000.0);
+    span.in_scope(|| tracing::info!("request completed"));
+
+    let metrics = metrics();
+    metrics
+        .requests
+        .with_label_values(&[method.as_str(), &route, status.as_str()])
+        .inc();
+    metrics
+        .request_duration
+        .with_label_values(&[method.as_str(), &route])
+        .observe(latency.as_secs_f64());
+
+    let viewed = method == Method::GET && matches!(status, StatusCode::OK | StatusCode::NOT_MODIFIED);
+    if viewed && route.ends_with("/:post") {
+        if let (Some(collection), Some((_, slug))) =
+            (site_config().collection_for_href(&path), path.rsplit_once('/'))
+        {
+            metrics
+                .post_views
+                .with_label_values(&[collection.name.as_str(), slug])
+                .inc();
+        }
+    }
+    response
+}
+
+// Prometheus text exposition of every metric.
+pub async fn metrics_handler() -> Response {
+    match metrics().encode() {
+        Ok(body) => (
+            [
+                (CONTENT_TYPE, HeaderValue::from_static("text/plain; version=0.0.4")),
+                (CACHE_CONTROL, HeaderValue::from_static("no-store")),
+            ],
+            body,
+        )
+            .into_response(),
+        Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, format!("Error encoding metrics: {e}")).into_response(),
+    }
+}